@@ -15,6 +15,7 @@ import (
 type Todo struct {
 	ID   int32
 	Note string
+	Done bool
 }
 
 // Appはラッパー構造体
@@ -42,6 +43,16 @@ func (a *App) GetTodoCount() int {
 	return int(C.get_todo_count(a.ptr))
 }
 
+// SetTodoDoneは指定されたIDのTodoの完了状態を設定します
+func (a *App) SetTodoDone(id int32, done bool) bool {
+	return bool(C.set_todo_done(a.ptr, C.int32_t(id), C.bool(done)))
+}
+
+// IsTodoDoneは指定されたIDのTodoが完了しているかどうかを返します
+func (a *App) IsTodoDone(id int32) bool {
+	return bool(C.is_todo_done(a.ptr, C.int32_t(id)))
+}
+
 // GetTodoAtは指定されたインデックスのTodoを返します
 func (a *App) GetTodoAt(index int) *Todo {
 	if index >= a.GetTodoCount() {
@@ -50,15 +61,18 @@ func (a *App) GetTodoAt(index int) *Todo {
 
 	id := int32(C.get_todo_id_at(a.ptr, C.size_t(index)))
 
-	// get_todo_note_atはメモリを確保して返すので、Goで解放する必要があります
+	// get_todo_note_atはRust側で確保した文字列の所有権を呼び出し側に渡します。
+	// C.GoStringでGoの文字列にコピーした後、free_stringでRust側に返して解放してください。
+	// C.freeで解放したり、解放後にcNoteを参照したりしてはいけません。
 	cNote := C.get_todo_note_at(a.ptr, C.size_t(index))
 	note := C.GoString(cNote)
 	// Rust側で確保したメモリを解放
-	C.free_char_p_box(cNote)
+	C.free_string(cNote)
 
 	return &Todo{
 		ID:   id,
 		Note: note,
+		Done: a.IsTodoDone(id),
 	}
 }
 
@@ -78,6 +92,9 @@ func main() {
 	app.AddTodo(2, "レポートを書く")
 	app.AddTodo(3, "友達に電話する")
 
+	// Todoを完了にする
+	app.SetTodoDone(1, true)
+
 	// Todoの数を取得
 	count := app.GetTodoCount()
 	fmt.Printf("Todo数: %d\n", count)
@@ -86,7 +103,7 @@ func main() {
 	for i := range count {
 		todo := app.GetTodoAt(i)
 		if todo != nil {
-			fmt.Printf("Todo[%d]: ID=%d, Note=%s\n", i, todo.ID, todo.Note)
+			fmt.Printf("Todo[%d]: ID=%d, Note=%s, Done=%t\n", i, todo.ID, todo.Note, todo.Done)
 		}
 	}
 }
@@ -76,6 +76,35 @@ func TestGetTodo(t *testing.T) {
 	}
 }
 
+// TestTodoDone はTodoの完了状態の切り替えをテストします
+func TestTodoDone(t *testing.T) {
+	app := NewApp()
+	defer app.Free()
+
+	app.AddTodo(1, "タスク1")
+
+	if app.IsTodoDone(1) {
+		t.Errorf("追加直後のTodoが完了になっています")
+	}
+
+	if !app.SetTodoDone(1, true) {
+		t.Errorf("完了状態の設定に失敗")
+	}
+	if todo := app.GetTodoAt(0); todo == nil || !todo.Done {
+		t.Errorf("完了にしたTodoが完了になっていません: %+v", todo)
+	}
+
+	app.SetTodoDone(1, false)
+	if app.IsTodoDone(1) {
+		t.Errorf("未完了に戻したTodoが完了のままです")
+	}
+
+	// 存在しないIDの場合はfalseが返ることを確認
+	if app.SetTodoDone(99, true) {
+		t.Errorf("存在しないIDでtrueが返された")
+	}
+}
+
 func formatBytes(bytes uint64) (float64, string) {
 	// 人間が読みやすい単位に変換
 	var unit string
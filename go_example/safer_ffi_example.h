@@ -17,6 +17,67 @@ extern "C" {
 #include <stddef.h>
 #include <stdint.h>
 
+/** \brief
+ *  Todoの進行状態を表す列挙型
+ *
+ *  `Todo`（未着手）→`Doing`（作業中）→`Done`（完了）の3つの状態を持ちます。
+ *  C側では`uint8_t`として扱われます。
+ */
+/** \remark Has the same ABI as `uint8_t` **/
+#ifdef DOXYGEN
+typedef
+#endif
+enum Status {
+    /** \brief
+     *  未着手
+     */
+    STATUS_TODO,
+    /** \brief
+     *  作業中
+     */
+    STATUS_DOING,
+    /** \brief
+     *  完了
+     */
+    STATUS_DONE,
+}
+#ifndef DOXYGEN
+; typedef uint8_t
+#endif
+Status_t;
+
+
+#include <stdbool.h>
+
+/** \brief
+ *  Todoの優先度を表す列挙型
+ *
+ *  `Low`（低）、`Medium`（中）、`High`（高）の3段階で、既定値は`Medium`です。
+ *  C側では`uint8_t`として扱われます。
+ */
+/** \remark Has the same ABI as `uint8_t` **/
+#ifdef DOXYGEN
+typedef
+#endif
+enum Priority {
+    /** \brief
+     *  低
+     */
+    PRIORITY_LOW,
+    /** \brief
+     *  中
+     */
+    PRIORITY_MEDIUM,
+    /** \brief
+     *  高
+     */
+    PRIORITY_HIGH,
+}
+#ifndef DOXYGEN
+; typedef uint8_t
+#endif
+Priority_t;
+
 /** \brief
  *  Todoアイテムを表す構造体
  *
@@ -26,6 +87,15 @@ extern "C" {
  *
  *  * `id` - Todo項目の一意識別子
  *  * `note` - Todo項目の内容を表す文字列（FFI互換のchar_p::Box型）
+ *  * `status` - Todo項目の進行状態
+ *  * `done` - Todo項目が完了しているかどうか（`status`が`Status::Done`の場合のみ`true`）
+ *  * `category` - Todo項目の分類（未分類の場合は空文字列）
+ *  * `priority` - Todo項目の優先度
+ *
+ *  # 注意
+ *
+ *  フィールドを追加するとC側の構造体のレイアウトが変わります。
+ *  Rust側を更新した場合は、ヘッダーファイルを再生成してC/Go側も再ビルドしてください。
  *
  *  # 使用例
  *
@@ -44,6 +114,18 @@ typedef struct Todo {
 
     /** <No documentation available> */
     char * note;
+
+    /** <No documentation available> */
+    Status_t status;
+
+    /** <No documentation available> */
+    bool done;
+
+    /** <No documentation available> */
+    char * category;
+
+    /** <No documentation available> */
+    Priority_t priority;
 } Todo_t;
 
 /** \brief
@@ -68,6 +150,14 @@ typedef struct Vec_Todo {
  *  # フィールド
  *
  *  * `todos` - Todo項目のコレクション（FFI互換のrepr_c::Vec型）
+ *  * `next_id` - `add_todo_auto`で次に割り当てるID（初期値は1）
+ *  * `on_change` - Todoの追加・削除時に呼び出されるコールバック（`set_on_change`で設定）
+ *  * `max_todos` - 追加できるTodoの最大数（0の場合は無制限、`set_max_todos`で設定）
+ *
+ *  # 注意
+ *
+ *  フィールドを追加するとC側の構造体のレイアウトが変わります。
+ *  C/Go側では`app_new`で作成したポインタを介してのみ扱い、フィールドに直接アクセスしないでください。
  *
  *  # 使用例
  *
@@ -90,14 +180,61 @@ typedef struct Vec_Todo {
 typedef struct App {
     /** <No documentation available> */
     Vec_Todo_t todos;
-} App_t;
 
+    /** <No documentation available> */
+    int32_t next_id;
 
-#include <stdbool.h>
+    /** <No documentation available> */
+    void (*on_change)(int32_t);
+
+    /** <No documentation available> */
+    size_t max_todos;
+} App_t;
+
+/** \brief
+ *  タイムスタンプ付きのログ形式のTodoを追加します
+ *
+ *  ノートは`[<timestamp>] <message>`の形式で保存されます。
+ *  `message`は最初のNULバイトで終わるため、組み立てたノートにNULバイトが含まれることはありません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+ *  * `id` - 追加するTodoの一意識別子
+ *  * `message` - ログメッセージ
+ *  * `timestamp` - ノートの先頭に付与するタイムスタンプ（UNIX時間など）
+ *
+ *  # 戻り値
+ *
+ *  追加が成功した場合は`true`を返します。同じIDのTodoが既に存在する場合や、
+ *  Todoの数が`set_max_todos`で設定した上限に達している場合は追加せずに`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_log_entry};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let message = CString::new("起動しました").unwrap();
+ *  assert!(add_log_entry(&mut app, 1, char_p::Ref::from(message.as_ref()), 1700000000));
+ *  assert_eq!(app.todos[0].note.to_str(), "[1700000000] 起動しました");
+ *  ```
+ */
+bool
+add_log_entry (
+    App_t * app,
+    int32_t id,
+    char const * message,
+    int64_t timestamp);
 
 /** \brief
  *  Todoをアプリケーションに追加します
  *
+ *  `add_todo_checked`の結果を`bool`で返す薄いラッパーです。
+ *  失敗した理由を知りたい場合は`add_todo_checked`を使用してください。
+ *
  *  # 引数
  *
  *  * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
@@ -106,7 +243,7 @@ typedef struct App {
  *
  *  # 戻り値
  *
- *  追加が成功した場合は`true`、失敗した場合は`false`を返します。
+ *  追加が成功した場合は`true`、失敗した場合（同じIDのTodoが既に存在する場合など）は`false`を返します。
  *
  *  # 使用例
  *
@@ -145,246 +282,3886 @@ add_todo (
     char const * note);
 
 /** \brief
- *  アプリケーションのメモリを解放します
+ *  英数字と空白のみからなるノートの場合だけTodoを追加します
  *
- *  この関数を呼び出すことで、アプリケーションが使用していたメモリリソースが
- *  適切に解放されます。Go言語からの利用時には、defer文を使用して確実に呼び出すことが推奨されます。
+ *  入力を厳しく制限したい場合に利用します。英数字はUnicodeの定義に従うため、
+ *  漢字やひらがななども使用できます。
  *
  *  # 引数
  *
- *  * `_app` - 解放するアプリケーションインスタンス
+ *  * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+ *  * `id` - 追加するTodoの一意識別子
+ *  * `note` - Todoの内容を表す文字列
  *
- *  # 注意
+ *  # 戻り値
  *
- *  この関数内では特別な処理は行われず、Rustの所有権システムによって自動的にメモリが解放されます。
- *  repr_c::Box はドロップ時に自動的にメモリを解放します。
+ *  追加に成功した場合は`true`を返します。ノートに英数字と半角スペース以外の文字
+ *  （記号や改行など）が含まれる場合は追加せずに`false`を返します。
+ *  その他の失敗条件は`add_todo`と同じです。
  *
  *  # 使用例
  *
- *  ## Go
- *
- *  ```go
- *  import "example.com/todo"
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo_alnum_only};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
  *
- *  func main() {
- *  app := todo.AppNew()
- *  defer todo.AppFree(app) // 確実にメモリを解放
+ *  let mut app = App::default();
+ *  let valid = CString::new("Buy milk 2").unwrap();
+ *  let invalid = CString::new("Buy milk!").unwrap();
  *
- *  // アプリの操作...
- *  }
+ *  assert!(add_todo_alnum_only(&mut app, 1, char_p::Ref::from(valid.as_ref())));
+ *  assert!(!add_todo_alnum_only(&mut app, 2, char_p::Ref::from(invalid.as_ref())));
  *  ```
  */
-void
-app_free (
-    App_t * _app);
+bool
+add_todo_alnum_only (
+    App_t * app,
+    int32_t id,
+    char const * note);
 
 /** \brief
- *  新しいAppインスタンスを作成します
+ *  IDを自動で割り当ててTodoを追加します
+ *
+ *  呼び出し側でIDを管理せずにTodoを追加したい場合に利用します。
+ *  `app.next_id`から順に未使用のIDを探して割り当て、`next_id`をその次の値に進めます。
+ *  `next_id`は削除によって巻き戻らないため、削除したTodoのIDが再利用されることはありません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `note` - Todoの内容を表す文字列への参照
  *
  *  # 戻り値
  *
- *  空のTodoリストを持つAppのインスタンスをFFI互換のBoxでラップして返します。
+ *  割り当てたIDを返します。割り当て可能なIDが残っていない場合は`-1`を返し、
+ *  Todoは追加されません。
  *
  *  # 使用例
  *
- *  ## Rust
- *
  *  ```rust
- *  use safer_ffi_example::app_new;
+ *  use safer_ffi_example::{App, add_todo_auto};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
  *
- *  let app = app_new();
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *
+ *  assert_eq!(add_todo_auto(&mut app, char_p::Ref::from(note.as_ref())), 1);
+ *  assert_eq!(add_todo_auto(&mut app, char_p::Ref::from(note.as_ref())), 2);
  *  ```
+ */
+int32_t
+add_todo_auto (
+    App_t * app,
+    char const * note);
+
+/** \brief
+ *  Todoの追加結果
+ */
+/** \remark Has the same ABI as `uint8_t` **/
+#ifdef DOXYGEN
+typedef
+#endif
+enum AddResult {
+    /** \brief
+     *  追加に成功した
+     */
+    ADD_RESULT_OK,
+    /** \brief
+     *  同じIDのTodoが既に存在する
+     */
+    ADD_RESULT_DUPLICATE_ID,
+    /** \brief
+     *  ノートがNULバイトを含むなど、Todoのノートとして扱えない
+     */
+    ADD_RESULT_INVALID_NOTE,
+    /** \brief
+     *  Todoの数が`max_todos`に達している
+     */
+    ADD_RESULT_LIMIT_REACHED,
+}
+#ifndef DOXYGEN
+; typedef uint8_t
+#endif
+AddResult_t;
+
+/** \brief
+ *  Todoをアプリケーションに追加し、結果を返します
  *
- *  ## Go
+ *  追加に成功した場合は、`set_on_change`で設定したコールバックを追加したIDで呼び出します。
  *
- *  ```go
- *  import "example.com/todo"
+ *  # 引数
  *
- *  func main() {
- *  app := todo.AppNew()
- *  defer todo.AppFree(app)
- *  // アプリを使用...
- *  }
+ *  * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+ *  * `id` - 追加するTodoの一意識別子
+ *  * `note` - Todoの内容を表す文字列（FFI互換のchar_p::Ref型）
+ *
+ *  # 戻り値
+ *
+ *  追加に成功した場合は`AddResult::Ok`を返します。
+ *  同じIDのTodoが既に存在する場合は`AddResult::DuplicateId`、
+ *  ノートとして扱えない文字列の場合は`AddResult::InvalidNote`、
+ *  Todoの数が`set_max_todos`で設定した上限に達している場合は`AddResult::LimitReached`を返し、
+ *  Todoは追加されません。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{AddResult, App, add_todo_checked};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("重要なタスク").unwrap();
+ *
+ *  assert_eq!(add_todo_checked(&mut app, 1, char_p::Ref::from(note.as_ref())), AddResult::Ok);
+ *  assert_eq!(
+ *  add_todo_checked(&mut app, 1, char_p::Ref::from(note.as_ref())),
+ *  AddResult::DuplicateId
+ *  );
  *  ```
  */
-App_t *
-app_new (void);
-
-/** <No documentation available> */
-void
-free_char_p_box (
-    char * _boxed);
+AddResult_t
+add_todo_checked (
+    App_t * app,
+    int32_t id,
+    char const * note);
 
 /** \brief
- *  アプリケーション内のTodoの数を取得します
+ *  キーと値を組み合わせたノートのTodoを追加します
+ *
+ *  ノートは`<key>: <value>`の形式で保存されます。ホスト側で文字列を連結せずに
+ *  整形済みのノートを追加したい場合に利用します。`key`と`value`はそれぞれ最初のNULバイトで
+ *  終わるため、組み立てたノートにNULバイトが含まれることはありません。
  *
  *  # 引数
  *
- *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+ *  * `id` - 追加するTodoの一意識別子
+ *  * `key` - ノートの先頭に置くキー
+ *  * `value` - キーに続けて置く値
  *
  *  # 戻り値
  *
- *  アプリケーション内のTodoアイテムの数
+ *  追加が成功した場合は`true`を返します。同じIDのTodoが既に存在する場合や、
+ *  Todoの数が`set_max_todos`で設定した上限に達している場合は追加せずに`false`を返します。
  *
  *  # 使用例
  *
- *  ## Rust
- *
  *  ```rust
- *  use safer_ffi_example::{App, add_todo, get_todo_count};
+ *  use safer_ffi_example::{App, add_todo_kv};
  *  use safer_ffi::prelude::*;
  *  use std::ffi::CString;
  *
  *  let mut app = App::default();
- *  assert_eq!(get_todo_count(&app), 0);
+ *  let key = CString::new("買い物").unwrap();
+ *  let value = CString::new("牛乳").unwrap();
+ *  assert!(add_todo_kv(
+ *  &mut app,
+ *  1,
+ *  char_p::Ref::from(key.as_ref()),
+ *  char_p::Ref::from(value.as_ref()),
+ *  ));
+ *  assert_eq!(app.todos[0].note.to_str(), "買い物: 牛乳");
+ *  ```
+ */
+bool
+add_todo_kv (
+    App_t * app,
+    int32_t id,
+    char const * key,
+    char const * value);
+
+/** \brief
+ *  `&'lt [T]` but with a guaranteed `#[repr(C)]` layout.
  *
- *  // Todoを追加
- *  let note = CString::new("タスク").unwrap();
- *  let note_ref = char_p::Ref::from(note.as_ref());
- *  add_todo(&mut app, 1, note_ref);
+ *  # C layout (for some given type T)
  *
- *  assert_eq!(get_todo_count(&app), 1);
+ *  ```c
+ *  typedef struct {
+ *  // Cannot be NULL
+ *  T * ptr;
+ *  size_t len;
+ *  } slice_T;
  *  ```
  *
- *  ## Go
+ *  # Nullable pointer?
  *
- *  ```go
- *  import (
- *  "example.com/todo"
- *  "fmt"
- *  )
+ *  If you want to support the above typedef, but where the `ptr` field is
+ *  allowed to be `NULL` (with the contents of `len` then being undefined)
+ *  use the `Option< slice_ptr<_> >` type.
+ */
+typedef struct slice_ref_int32 {
+    /** \brief
+     *  Pointer to the first element (if any).
+     */
+    int32_t const * ptr;
+
+    /** \brief
+     *  Element count
+     */
+    size_t len;
+} slice_ref_int32_t;
+
+/** \brief
+ *  `&'lt [T]` but with a guaranteed `#[repr(C)]` layout.
  *
- *  func main() {
- *  app := todo.AppNew()
- *  defer todo.AppFree(app)
+ *  # C layout (for some given type T)
  *
- *  todo.AddTodo(app, 1, "重要なタスク")
- *  count := todo.GetTodoCount(app)
- *  fmt.Printf("Todo数: %d\n", count)
- *  }
+ *  ```c
+ *  typedef struct {
+ *  // Cannot be NULL
+ *  T * ptr;
+ *  size_t len;
+ *  } slice_T;
  *  ```
+ *
+ *  # Nullable pointer?
+ *
+ *  If you want to support the above typedef, but where the `ptr` field is
+ *  allowed to be `NULL` (with the contents of `len` then being undefined)
+ *  use the `Option< slice_ptr<_> >` type.
  */
-size_t
-get_todo_count (
-    App_t const * app);
+typedef struct slice_ref_char_const_ptr {
+    /** \brief
+     *  Pointer to the first element (if any).
+     */
+    char const * const * ptr;
+
+    /** \brief
+     *  Element count
+     */
+    size_t len;
+} slice_ref_char_const_ptr_t;
 
 /** \brief
- *  指定インデックスのTodoのIDを取得します
+ *  複数のTodoをまとめて追加します
+ *
+ *  FFIの呼び出しを1回で済ませ、リストの再確保も1回にまとめます。
+ *  `ids`と`notes`の同じ位置の要素から1件のTodoを作成します。
  *
  *  # 引数
  *
- *  * `app` - Todoアプリケーションインスタンスへの参照
- *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *  * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+ *  * `ids` - 追加するTodoのIDの配列
+ *  * `notes` - 追加するTodoのノートの配列
  *
  *  # 戻り値
  *
- *  成功した場合はTodoのID、インデックスが範囲外の場合は-1を返します
+ *  実際に追加したTodoの数を返します。`ids`と`notes`の長さが異なる場合は、
+ *  短い方の長さまでを追加します。`add_todo`と同様に、既に存在するIDや
+ *  同じ呼び出しの中で先に追加したIDと重複するTodoは追加されません。
+ *  `set_max_todos`で上限を設定している場合は、上限に達した時点で残りを追加しません。
  *
  *  # 使用例
  *
- *  ## Rust
- *
  *  ```rust
- *  use safer_ffi_example::{App, add_todo, get_todo_id_at};
+ *  use safer_ffi_example::{App, add_todos, get_todo_count};
  *  use safer_ffi::prelude::*;
  *  use std::ffi::CString;
  *
  *  let mut app = App::default();
+ *  let milk = CString::new("牛乳を買う").unwrap();
+ *  let egg = CString::new("卵を買う").unwrap();
+ *  let notes = [char_p::Ref::from(milk.as_ref()), char_p::Ref::from(egg.as_ref())];
  *
- *  // インデックスが範囲外の場合は-1を返す
- *  assert_eq!(get_todo_id_at(&app, 0), -1);
- *
- *  // Todoを追加
- *  let note = CString::new("タスク").unwrap();
- *  let note_ref = char_p::Ref::from(note.as_ref());
- *  add_todo(&mut app, 42, note_ref);
- *
- *  // 追加したTodoのIDを取得
- *  assert_eq!(get_todo_id_at(&app, 0), 42);
+ *  assert_eq!(add_todos(&mut app, [1, 2][..].into(), notes[..].into()), 2);
+ *  assert_eq!(get_todo_count(&app), 2);
  *  ```
+ */
+size_t
+add_todos (
+    App_t * app,
+    slice_ref_int32_t ids,
+    slice_ref_char_const_ptr_t notes);
+
+/** \brief
+ *  すべてのTodoが条件を満たすかどうかを判定します
  *
- *  ## Go
+ *  条件を満たさないTodoが見つかった時点で走査を終了し、以降のTodoに対して
+ *  `predicate`は呼び出されません。
  *
- *  ```go
- *  import (
- *  "example.com/todo"
- *  "fmt"
- *  )
+ *  # 引数
  *
- *  func main() {
- *  app := todo.AppNew()
- *  defer todo.AppFree(app)
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `predicate` - 各Todoに対して呼び出される判定関数（渡されるノートへの参照は呼び出し中のみ有効）
+ *  * `user_data` - `predicate`にそのまま渡される任意のポインタ
  *
- *  todo.AddTodo(app, 42, "重要なタスク")
- *  id := todo.GetTodoIdAt(app, 0)
- *  fmt.Printf("最初のTodoのID: %d\n", id)
- *  }
- *  ```
+ *  # 戻り値
+ *
+ *  すべてのTodoが条件を満たす場合は`true`を返します。Todoが存在しない場合も`true`を返します。
  */
-int32_t
-get_todo_id_at (
+bool
+all_todos_match (
     App_t const * app,
-    size_t index);
+    bool (*predicate)(int32_t, char const *, void *),
+    void * user_data);
 
 /** \brief
- *  指定インデックスのTodoのノート（内容）を取得します
+ *  条件を満たすTodoが1つでも存在するかどうかを判定します
+ *
+ *  条件を満たすTodoが見つかった時点で走査を終了し、以降のTodoに対して
+ *  `predicate`は呼び出されません。
  *
  *  # 引数
  *
  *  * `app` - Todoアプリケーションインスタンスへの参照
- *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *  * `predicate` - 各Todoに対して呼び出される判定関数（渡されるノートへの参照は呼び出し中のみ有効）
+ *  * `user_data` - `predicate`にそのまま渡される任意のポインタ
  *
  *  # 戻り値
  *
- *  成功した場合はTodoのノート、インデックスが範囲外の場合は空文字列を返します
+ *  条件を満たすTodoが存在する場合は`true`、存在しない場合（空の場合を含む）は`false`を返します。
  *
  *  # 使用例
  *
- *  ## Rust
- *
  *  ```rust
- *  use safer_ffi_example::{App, add_todo, get_todo_note_at};
+ *  use safer_ffi_example::{App, add_todo, any_todo_matches};
  *  use safer_ffi::prelude::*;
- *  use std::ffi::CString;
+ *  use std::ffi::{c_void, CString};
  *
- *  let mut app = App::default();
+ *  extern "C" fn is_urgent(_id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) -> bool {
+ *  note.to_str().contains("至急")
+ *  }
  *
- *  // インデックスが範囲外の場合は空文字列を返す
- *  let empty = get_todo_note_at(&app, 0);
- *  assert_eq!(empty.to_str(), "");
+ *  let mut app = App::default();
+ *  let note = CString::new("至急: 電話する").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
  *
- *  // Todoを追加
- *  let note = CString::new("重要なタスク").unwrap();
- *  let note_ref = char_p::Ref::from(note.as_ref());
- *  add_todo(&mut app, 1, note_ref);
+ *  assert!(any_todo_matches(&app, is_urgent, std::ptr::null_mut()));
+ *  ```
+ */
+bool
+any_todo_matches (
+    App_t const * app,
+    bool (*predicate)(int32_t, char const *, void *),
+    void * user_data);
+
+/** \brief
+ *  複数の変更をまとめて適用・破棄するためのトランザクション
  *
- *  // 追加したTodoのノートを取得
- *  let retrieved = get_todo_note_at(&app, 0);
- *  assert_eq!(retrieved.to_str(), "重要なタスク");
+ *  `app_begin`で作成した時点のアプリケーションのコピーを作業用に保持します。
+ *  作業用のAppは`tx_app`で取得でき、`add_todo`などの通常の変更関数をそのまま適用できます。
+ *  変更は`tx_commit`を呼び出すまで元のアプリケーションには反映されません。
+ *
+ *  作業用のAppには`on_change`を引き継がないため、トランザクション内の変更では
+ *  コールバックは呼び出されません。コミット時に差分の分だけまとめて呼び出されます。
+ *
+ *  # 注意
+ *
+ *  トランザクションは元のアプリケーションへのポインタを保持します。
+ *  元のアプリケーションを参照する`tx_commit`と`tx_rollback`は`unsafe`で、
+ *  呼び出し側が元のアプリケーションの生存と排他的なアクセスを保証する必要があります。
+ */
+typedef struct Transaction Transaction_t;
+
+/** \brief
+ *  アプリケーションに対するトランザクションを開始します
+ *
+ *  # 引数
+ *
+ *  * `app` - トランザクションの対象となるアプリケーションインスタンスへの可変参照
+ *
+ *  # 戻り値
+ *
+ *  現在の状態をコピーしたトランザクションを返します。`tx_free`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, app_begin, tx_app, tx_commit, tx_free};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let mut tx = app_begin(&mut app);
+ *
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(tx_app(&mut tx), 1, char_p::Ref::from(note.as_ref()));
+ *  // Safety: `app`はトランザクションの解放まで生存し、他からは参照されていない
+ *  unsafe { tx_commit(&mut tx) };
+ *  tx_free(tx);
+ *
+ *  assert_eq!(app.todos.len(), 1);
  *  ```
+ */
+Transaction_t *
+app_begin (
+    App_t * app);
+
+/** \brief
+ *  アプリケーションのメモリを解放します
+ *
+ *  この関数を呼び出すことで、アプリケーションが使用していたメモリリソースが
+ *  適切に解放されます。Go言語からの利用時には、defer文を使用して確実に呼び出すことが推奨されます。
+ *
+ *  # 引数
+ *
+ *  * `_app` - 解放するアプリケーションインスタンス
+ *
+ *  # 注意
+ *
+ *  この関数内では特別な処理は行われず、Rustの所有権システムによって自動的にメモリが解放されます。
+ *  repr_c::Box はドロップ時に自動的にメモリを解放します。
+ *
+ *  # 使用例
  *
  *  ## Go
  *
  *  ```go
- *  import (
- *  "example.com/todo"
- *  "fmt"
- *  )
+ *  import "example.com/todo"
  *
  *  func main() {
  *  app := todo.AppNew()
- *  defer todo.AppFree(app)
+ *  defer todo.AppFree(app) // 確実にメモリを解放
  *
- *  todo.AddTodo(app, 1, "買い物リスト")
- *  note := todo.GetTodoNoteAt(app, 0)
- *  fmt.Printf("Todo内容: %s\n", note)
+ *  // アプリの操作...
  *  }
  *  ```
  */
-char *
-get_todo_note_at (
+void
+app_free (
+    App_t * _app);
+
+/** \brief
+ *  タブ区切り（TSV）のテキストからアプリケーションを作成します
+ *
+ *  各行を`id<TAB>note`として解釈します。行末の`\r`は取り除かれるため、
+ *  CRLF改行のテキストもそのまま読み込めます。
+ *
+ *  # 引数
+ *
+ *  * `text` - 読み込むTSVテキスト
+ *
+ *  # 戻り値
+ *
+ *  読み込んだTodoを持つAppのインスタンスを返します。
+ *  タブを含まない行やIDを整数として解釈できない行は読み飛ばされます。
+ *  返されたAppは`app_free`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::app_from_tsv;
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let text = CString::new("1\t牛乳を買う\n2\tレポートを書く\n").unwrap();
+ *  let app = app_from_tsv(char_p::Ref::from(text.as_ref()));
+ *
+ *  assert_eq!(app.todos.len(), 2);
+ *  assert_eq!(app.todos[1].note.to_str(), "レポートを書く");
+ *  ```
+ */
+App_t *
+app_from_tsv (
+    char const * text);
+
+/** \brief
+ *  `app_save_versioned`で保存したファイルからアプリケーションを読み込みます
+ *
+ *  # 引数
+ *
+ *  * `path` - 読み込むファイルのパス
+ *
+ *  # 戻り値
+ *
+ *  読み込んだアプリケーションを返します。ファイルを読み込めない場合、スキーマバージョンが
+ *  未知のバージョンの場合、データが不正な場合は空のアプリケーションを返します。
+ *  返されたアプリケーションは`app_free`で解放してください。
+ */
+App_t *
+app_load_versioned (
+    char const * path);
+
+/** \brief
+ *  新しいAppインスタンスを作成します
+ *
+ *  # 戻り値
+ *
+ *  空のTodoリストを持つAppのインスタンスをFFI互換のBoxでラップして返します。
+ *
+ *  # 使用例
+ *
+ *  ## Rust
+ *
+ *  ```rust
+ *  use safer_ffi_example::app_new;
+ *
+ *  let app = app_new();
+ *  ```
+ *
+ *  ## Go
+ *
+ *  ```go
+ *  import "example.com/todo"
+ *
+ *  func main() {
+ *  app := todo.AppNew()
+ *  defer todo.AppFree(app)
+ *  // アプリを使用...
+ *  }
+ *  ```
+ */
+App_t *
+app_new (void);
+
+/** \brief
+ *  `&'lt [T]` but with a guaranteed `#[repr(C)]` layout.
+ *
+ *  # C layout (for some given type T)
+ *
+ *  ```c
+ *  typedef struct {
+ *  // Cannot be NULL
+ *  T * ptr;
+ *  size_t len;
+ *  } slice_T;
+ *  ```
+ *
+ *  # Nullable pointer?
+ *
+ *  If you want to support the above typedef, but where the `ptr` field is
+ *  allowed to be `NULL` (with the contents of `len` then being undefined)
+ *  use the `Option< slice_ptr<_> >` type.
+ */
+typedef struct slice_ref_uint8 {
+    /** \brief
+     *  Pointer to the first element (if any).
+     */
+    uint8_t const * ptr;
+
+    /** \brief
+     *  Element count
+     */
+    size_t len;
+} slice_ref_uint8_t;
+
+/** \brief
+ *  `app_snapshot`で取得したスナップショットの内容にアプリケーションを戻します
+ *
+ *  Todoの一覧のみを置き換え、`next_id`、`on_change`、`max_todos`はそのまま維持します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `blob` - `app_snapshot`で取得したバイト列
+ *
+ *  # 戻り値
+ *
+ *  復元した場合は`true`を返します。スナップショットが不正な場合、スキーマバージョンが
+ *  未知の場合、Todoの数が`set_max_todos`で設定した上限を超える場合は`false`を返し、
+ *  アプリケーションは変更されません。
+ */
+bool
+app_restore (
+    App_t * app,
+    slice_ref_uint8_t blob);
+
+/** \brief
+ *  アプリケーションをスキーマバージョン付きのバイナリ形式でファイルに保存します
+ *
+ *  先頭2バイトにスキーマバージョン（リトルエンディアンの`u16`）を書き込み、
+ *  続けてID、ノート、状態、完了フラグ、分類、優先度を含むすべてのTodoを書き込みます。
+ *  ファイルが既に存在する場合は上書きします。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `path` - 保存先のファイルパス
+ *
+ *  # 戻り値
+ *
+ *  保存できた場合は`true`、ファイルへの書き込みに失敗した場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, app_load_versioned, app_save_versioned};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let path = std::env::temp_dir().join("safer_ffi_example_doc_save_versioned.bin");
+ *  let c_path = CString::new(path.to_str().unwrap()).unwrap();
+ *
+ *  assert!(app_save_versioned(&app, char_p::Ref::from(c_path.as_ref())));
+ *  let restored = app_load_versioned(char_p::Ref::from(c_path.as_ref()));
+ *  assert_eq!(restored.todos[0].note.to_str(), "牛乳を買う");
+ *  # std::fs::remove_file(path).unwrap();
+ *  ```
+ */
+bool
+app_save_versioned (
     App_t const * app,
-    size_t index);
+    char const * path);
+
+/** \brief
+ *  Same as [`Vec<T>`][`rust::Vec`], but with guaranteed `#[repr(C)]` layout
+ */
+typedef struct Vec_uint8 {
+    /** <No documentation available> */
+    uint8_t * ptr;
+
+    /** <No documentation available> */
+    size_t len;
+
+    /** <No documentation available> */
+    size_t cap;
+} Vec_uint8_t;
+
+/** \brief
+ *  アプリケーションの現在の内容をスナップショットとして取得します
+ *
+ *  `app_save_versioned`と同じスキーマバージョン付きのバイナリ形式で、メモリ上に保存します。
+ *  `app_restore`と組み合わせて、変更前の状態に戻すチェックポイントとして利用します。
+ *  ID、ノート、状態、完了フラグ、分類、優先度を含むすべてのTodoを保存します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  スナップショットのバイト列を返します。内容は不透明なデータとして扱い、
+ *  `app_restore`にそのまま渡してください。返されたベクタは`free_u8_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, app_restore, app_snapshot, get_todo_count};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let snapshot = app_snapshot(&app);
+ *  add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert!(app_restore(&mut app, snapshot[..].into()));
+ *  assert_eq!(get_todo_count(&app), 1);
+ *  ```
+ */
+Vec_uint8_t
+app_snapshot (
+    App_t const * app);
+
+/** \brief
+ *  別のアプリケーションのTodoを移動して末尾に追加します
+ *
+ *  ノートの文字列は複製せずにそのまま移動するため、大量のTodoを統合する場合でも
+ *  追加のメモリ確保はリストの拡張分のみです。
+ *
+ *  # 引数
+ *
+ *  * `dst` - 追加先のアプリケーションインスタンスへの可変参照
+ *  * `src` - 追加元のアプリケーションインスタンス（この関数内で解放されます）
+ *
+ *  # 戻り値
+ *
+ *  実際に追加したTodoの数を返します。`dst`に`set_max_todos`で上限を設定している場合は、
+ *  上限に達した時点で残りを追加せず、追加されなかったTodoは`src`とともに解放されます。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{add_todo, app_new, append_and_consume, get_todo_count};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut dst = app_new();
+ *  let mut src = app_new();
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut dst, 1, char_p::Ref::from(note.as_ref()));
+ *  add_todo(&mut src, 2, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert_eq!(append_and_consume(&mut dst, src), 1);
+ *  assert_eq!(get_todo_count(&dst), 2);
+ *  ```
+ */
+size_t
+append_and_consume (
+    App_t * dst,
+    App_t * src);
+
+/** \brief
+ *  ノートが空、または空白文字のみのTodoの数を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  ノートが空文字列、またはスペース・タブ・改行などの空白文字だけで構成されている
+ *  Todoの数を返します。Todoが存在しない場合は0を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, blank_note_count};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "牛乳を買う"), (2, ""), (3, "  \t")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(blank_note_count(&app), 2);
+ *  ```
+ */
+size_t
+blank_note_count (
+    App_t const * app);
+
+/** \brief
+ *  Todoリストを指定した件数ごとに分割し、そのうちの1つを新しいアプリケーションとして取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `chunk_size` - 1つのチャンクに含めるTodoの件数
+ *  * `chunk_index` - 取得するチャンクのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  指定したチャンクのTodoをコピーした新しいAppを返します。最後のチャンクは
+ *  `chunk_size`件未満になる場合があります。`chunk_size`が0の場合や
+ *  `chunk_index`が範囲外の場合は空のAppを返します。
+ *  返されたAppは`app_free`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, chunk_app};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in 1..=5 {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  let chunk = chunk_app(&app, 2, 2);
+ *  assert_eq!(chunk.todos.len(), 1);
+ *  assert_eq!(chunk.todos[0].id, 5);
+ *  ```
+ */
+App_t *
+chunk_app (
+    App_t const * app,
+    size_t chunk_size,
+    size_t chunk_index);
+
+/** \brief
+ *  すべてのTodoを削除します
+ *
+ *  既存のTodoはノートも含めてすべて解放されます。
+ *  削除後もアプリケーションはそのまま利用できます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, clear_todos, get_todo_count};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  clear_todos(&mut app);
+ *  assert_eq!(get_todo_count(&app), 0);
+ *  ```
+ */
+void
+clear_todos (
+    App_t * app);
+
+/** \brief
+ *  すべてのノートに共通する最長の接頭辞を取得します
+ *
+ *  接頭辞は文字単位で比較するため、マルチバイト文字の途中で途切れることはありません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  共通の接頭辞を返します。Todoが1件の場合はそのノート全体を返し、
+ *  Todoが存在しない場合や先頭の文字から異なる場合は空文字列を返します。
+ *  返された文字列は`free_string`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, common_note_prefix};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "買い物: 牛乳"), (2, "買い物: 卵")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(common_note_prefix(&app).to_str(), "買い物: ");
+ *  ```
+ */
+char *
+common_note_prefix (
+    App_t const * app);
+
+/** \brief
+ *  `&'lt mut [T]` but with a guaranteed `#[repr(C)]` layout.
+ *
+ *  # C layout (for some given type T)
+ *
+ *  ```c
+ *  typedef struct {
+ *  // Cannot be NULL
+ *  T * ptr;
+ *  size_t len;
+ *  } slice_T;
+ *  ```
+ *
+ *  # Nullable pointer?
+ *
+ *  If you want to support the above typedef, but where the `ptr` field is
+ *  allowed to be `NULL` (with the contents of `len` then being undefined)
+ *  use the `Option< slice_ptr<_> >` type.
+ */
+typedef struct slice_mut_uint8 {
+    /** \brief
+     *  Pointer to the first element (if any).
+     */
+    uint8_t * ptr;
+
+    /** \brief
+     *  Element count
+     */
+    size_t len;
+} slice_mut_uint8_t;
+
+/** \brief
+ *  指定インデックスのTodoのノートを、UTF-8の文字境界で切り詰めてバッファにコピーします
+ *
+ *  バッファが足りない場合でも、文字の途中で切らずに収まるだけの文字をコピーし、
+ *  常にNUL終端された有効なUTF-8文字列を書き込みます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *  * `buf` - 書き込み先のバッファ（NUL終端の1バイトを含めた長さ）
+ *
+ *  # 戻り値
+ *
+ *  書き込んだバイト数（NUL終端を含まない）を返します。
+ *  インデックスが範囲外の場合は`-1`を返し、バッファは変更されません。
+ *  バッファの長さが0の場合は何も書き込まずに`0`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, copy_todo_note_truncated_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  // 「牛乳」の6バイトとNUL終端が収まり、「を」は途中で切らずに省かれる
+ *  let mut buf = [0u8; 8];
+ *  assert_eq!(copy_todo_note_truncated_at(&app, 0, (&mut buf[..]).into()), 6);
+ *  assert_eq!(&buf[..7], "牛乳\0".as_bytes());
+ *  ```
+ */
+ssize_t
+copy_todo_note_truncated_at (
+    App_t const * app,
+    size_t index,
+    slice_mut_uint8_t buf);
+
+/** \brief
+ *  完了しているTodoの数を取得します
+ *
+ *  `count_done(app) + count_pending(app)`は常に`get_todo_count(app)`と等しくなります。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  完了フラグが`true`のTodoの数を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, count_done, count_pending, set_todo_done};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *  add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+ *  set_todo_done(&mut app, 1, true);
+ *
+ *  assert_eq!(count_done(&app), 1);
+ *  assert_eq!(count_pending(&app), 1);
+ *  ```
+ */
+size_t
+count_done (
+    App_t const * app);
+
+/** \brief
+ *  指定した文字を含むノートの数を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `ch` - 検索する文字のUnicodeスカラー値（例: `'あ'`は`0x3042`）
+ *
+ *  # 戻り値
+ *
+ *  `ch`を1回以上含むノートの数を返します。1つのノートに複数回含まれていても1件として数えます。
+ *  `ch`がUnicodeスカラー値として不正な場合（サロゲートや`0x10FFFF`を超える値）は0を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Todo, count_notes_containing_char};
+ *
+ *  let mut app = App::default();
+ *  app.todos = vec![Todo::new(1, "牛乳を買う"), Todo::new(2, "卵を買う"), Todo::new(3, "走る")].into();
+ *
+ *  assert_eq!(count_notes_containing_char(&app, 'を' as u32), 2);
+ *  ```
+ */
+size_t
+count_notes_containing_char (
+    App_t const * app,
+    uint32_t ch);
+
+/** \brief
+ *  完了していないTodoの数を取得します
+ *
+ *  `count_done(app) + count_pending(app)`は常に`get_todo_count(app)`と等しくなります。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  完了フラグが`false`のTodoの数を返します。
+ */
+size_t
+count_pending (
+    App_t const * app);
+
+/** \brief
+ *  指定インデックスのTodoの状態を次に進めます
+ *
+ *  状態は`Todo`→`Doing`→`Done`→`Todo`の順に循環します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `index` - 対象のTodoのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  成功した場合は`true`、インデックスが範囲外の場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Status, Todo, cycle_status_at};
+ *
+ *  let mut app = App::default();
+ *  app.todos = vec![Todo::new(1, "タスク")].into();
+ *
+ *  assert!(cycle_status_at(&mut app, 0));
+ *  assert_eq!(app.todos[0].status, Status::Doing);
+ *  ```
+ */
+bool
+cycle_status_at (
+    App_t * app,
+    size_t index);
+
+/** \brief
+ *  重複を除いたノートの種類数を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  異なるノートの数を返します。Todoが存在しない場合は0を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, distinct_note_count};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "牛乳"), (2, "卵"), (3, "牛乳")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(distinct_note_count(&app), 2);
+ *  ```
+ */
+size_t
+distinct_note_count (
+    App_t const * app);
+
+/** \brief
+ *  条件を満たすTodoを取り除き、新しいアプリケーションとして取得します
+ *
+ *  アーカイブなどのために、条件に一致するTodoだけを元のアプリケーションから移動します。
+ *  移動したTodoと残ったTodoは、それぞれ元の順番を保ちます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `predicate` - 各Todoに対して呼び出される判定関数（渡されるノートへの参照は呼び出し中のみ有効）
+ *  * `user_data` - `predicate`にそのまま渡される任意のポインタ
+ *
+ *  # 戻り値
+ *
+ *  条件を満たしたTodoを持つ新しいAppを返します。返されたAppは`app_free`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, drain_matching};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::{c_void, CString};
+ *
+ *  extern "C" fn is_even(id: i32, _note: char_p::Ref<'_>, _user_data: *mut c_void) -> bool {
+ *  id % 2 == 0
+ *  }
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in 1..=4 {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  let drained = drain_matching(&mut app, is_even, std::ptr::null_mut());
+ *  assert_eq!(drained.todos.len(), 2);
+ *  assert_eq!(app.todos.len(), 2);
+ *  ```
+ */
+App_t *
+drain_matching (
+    App_t * app,
+    bool (*predicate)(int32_t, char const *, void *),
+    void * user_data);
+
+/** \brief
+ *  アプリケーションをJSONに変換した際のバイト数の見積もりを取得します
+ *
+ *  実際にJSON文字列を組み立てずに、バッファを事前に確保するための上限値を計算します。
+ *  ノートと分類はエスケープが必要な文字（`"`、`\`、制御文字）ごとに最大の長さで見積もります。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  JSONのバイト数の見積もりを返します。この値は実際の長さ以上であることが保証され、
+ *  実際の長さを下回ることはありません（多めに見積もられます）。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, estimated_json_size};
+ *
+ *  let app = App::default();
+ *  assert!(estimated_json_size(&app) >= "[]".len());
+ *  ```
+ */
+size_t
+estimated_json_size (
+    App_t const * app);
+
+/** \brief
+ *  C側から直接インデックスアクセスできる固定長のTodo表現
+ *
+ *  `note_ptr`はアプリケーション内部のノートのバイト列を指し、NUL終端は含みません。
+ *  `export_todo_c_array`で取得した配列の要素としてのみ利用します。
+ */
+typedef struct TodoC {
+    /** \brief
+     *  TodoのID
+     */
+    int32_t id;
+
+    /** \brief
+     *  ノートのUTF-8バイト列の先頭
+     */
+    uint8_t const * note_ptr;
+
+    /** \brief
+     *  ノートのバイト数（NUL終端を含まない）
+     */
+    size_t note_len;
+} TodoC_t;
+
+/** \brief
+ *  `export_todo_c_array`で作成した配列を保持するハンドル
+ */
+typedef struct TodoCArrayHandle TodoCArrayHandle_t;
+
+/** \brief
+ *  Todoリストを固定長の構造体配列として取得します
+ *
+ *  文字列の変換やコピーを行わずにC側から直接インデックスアクセスしたい場合に利用します。
+ *  配列の各要素はアプリケーション内部のノートを参照するため、
+ *  以下の間のみ有効です。
+ *
+ *  - 返されたハンドルを`free_todo_c_array`で解放するまで
+ *  - かつ、`app`を変更または解放するまで
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `out_ptr` - 配列の先頭へのポインタの書き込み先
+ *  * `out_len` - 配列の要素数の書き込み先
+ *
+ *  # 戻り値
+ *
+ *  配列を保持するハンドルを返します。使い終わったら`free_todo_c_array`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, export_todo_c_array, free_todo_c_array};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let mut ptr = std::ptr::null();
+ *  let mut len = 0;
+ *  let handle = export_todo_c_array(&app, &mut ptr, &mut len);
+ *
+ *  let todos = unsafe { std::slice::from_raw_parts(ptr, len) };
+ *  assert_eq!(todos[0].id, 1);
+ *  assert_eq!(todos[0].note_len, "牛乳を買う".len());
+ *
+ *  free_todo_c_array(handle);
+ *  ```
+ */
+TodoCArrayHandle_t *
+export_todo_c_array (
+    App_t const * app,
+    TodoC_t const * * out_ptr,
+    size_t * out_len);
+
+/** \brief
+ *  Same as [`Vec<T>`][`rust::Vec`], but with guaranteed `#[repr(C)]` layout
+ */
+typedef struct Vec_int32 {
+    /** <No documentation available> */
+    int32_t * ptr;
+
+    /** <No documentation available> */
+    size_t len;
+
+    /** <No documentation available> */
+    size_t cap;
+} Vec_int32_t;
+
+/** \brief
+ *  アプリケーションに存在するIDのうち、期待するIDに含まれないものを取得します
+ *
+ *  `missing_ids`と組み合わせることで、期待するIDの集合との差分をすべて求められます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `expected` - 存在するはずのIDの配列
+ *
+ *  # 戻り値
+ *
+ *  `expected`に含まれないTodoのIDをリストの順番で返します。
+ *  同じIDのTodoが複数ある場合も1回だけ返します。
+ *  返されたベクタは`free_i32_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, extra_ids};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in [1, 2, 3] {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(&extra_ids(&app, [1, 2][..].into())[..], &[3]);
+ *  ```
+ */
+Vec_int32_t
+extra_ids (
+    App_t const * app,
+    slice_ref_int32_t expected);
+
+/** \brief
+ *  ノートに指定した文字列を含むTodoだけを集めた新しいアプリケーションを取得します
+ *
+ *  元のアプリケーションは変更されません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `substring` - 検索する文字列（空文字列はすべてのTodoに一致します）
+ *
+ *  # 戻り値
+ *
+ *  一致したTodoを元の順番のままコピーした新しいAppを返します。一致するTodoがない場合は
+ *  空のAppを返します。返されたAppは`app_free`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, app_free, filter_todos_contains};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let milk = CString::new("牛乳を買う").unwrap();
+ *  let report = CString::new("レポートを書く").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(milk.as_ref()));
+ *  add_todo(&mut app, 2, char_p::Ref::from(report.as_ref()));
+ *
+ *  let substring = CString::new("買う").unwrap();
+ *  let filtered = filter_todos_contains(&app, char_p::Ref::from(substring.as_ref()));
+ *  assert_eq!(filtered.todos.len(), 1);
+ *  assert_eq!(filtered.todos[0].id, 1);
+ *  app_free(filtered);
+ *  ```
+ */
+App_t *
+filter_todos_contains (
+    App_t const * app,
+    char const * substring);
+
+/** \brief
+ *  検索文字列に最も近いノートを持つTodoのインデックスを取得します
+ *
+ *  ノートと検索文字列のレーベンシュタイン距離（文字単位の編集距離）を比較し、
+ *  多少の入力ミスがあっても目的のTodoを見つけられるようにします。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `query` - 検索文字列
+ *  * `max_distance` - 許容する最大の編集距離
+ *
+ *  # 戻り値
+ *
+ *  編集距離が`max_distance`以下で最も小さいTodoのインデックスを返します。
+ *  同じ距離の場合は先に出現したTodoを優先し、該当するTodoがない場合は-1を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, find_closest_note};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("buy milk").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let query = CString::new("buy mlk").unwrap();
+ *  assert_eq!(find_closest_note(&app, char_p::Ref::from(query.as_ref()), 1), 0);
+ *  ```
+ */
+ssize_t
+find_closest_note (
+    App_t const * app,
+    char const * query,
+    size_t max_distance);
+
+/** \brief
+ *  指定したIDのTodoのインデックスを取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `id` - 検索するTodoのID
+ *
+ *  # 戻り値
+ *
+ *  該当するTodoのインデックス（0から始まる）を返します。
+ *  同じIDのTodoが複数ある場合は最初の1件のインデックスを返し、
+ *  該当するTodoが存在しない場合は`-1`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, find_todo_index_by_id};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in [10, 20] {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(find_todo_index_by_id(&app, 20), 1);
+ *  assert_eq!(find_todo_index_by_id(&app, 30), -1);
+ *  ```
+ */
+ssize_t
+find_todo_index_by_id (
+    App_t const * app,
+    int32_t id);
+
+/** \brief
+ *  最初の未完了のTodoのインデックスを取得します
+ *
+ *  次に取り組むTodoに移動する場合などに利用します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  完了フラグが`false`の最初のTodoのインデックスを返します。
+ *  すべてのTodoが完了している場合やTodoが存在しない場合は`-1`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, first_pending_index, set_todo_done};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *  add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+ *  set_todo_done(&mut app, 1, true);
+ *
+ *  assert_eq!(first_pending_index(&app), 1);
+ *  ```
+ */
+ssize_t
+first_pending_index (
+    App_t const * app);
+
+/** \brief
+ *  指定した文字列を含むTodoをリストの先頭に移動します
+ *
+ *  安定な分割を行うため、先頭に移動したTodo同士、残りのTodo同士の順番は変わりません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `needle` - 検索する文字列
+ *
+ *  # 戻り値
+ *
+ *  先頭に移動したTodoの数を返します。`needle`が空文字列の場合は何もせずに`0`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, float_matching_to_top, get_todo_id_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "レポートを書く"), (2, "至急: 電話する")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  let needle = CString::new("至急").unwrap();
+ *  assert_eq!(float_matching_to_top(&mut app, char_p::Ref::from(needle.as_ref())), 1);
+ *  assert_eq!(get_todo_id_at(&app, 0), 2);
+ *  ```
+ */
+size_t
+float_matching_to_top (
+    App_t * app,
+    char const * needle);
+
+/** \brief
+ *  Todoをインデックスとともに順番に走査します
+ *
+ *  行ごとに背景色を交互に変えるなど、インデックスを使って描画したい場合に利用します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `callback` - 各Todoに対して、インデックス（0から始まる）とともに呼び出される関数
+ *  （渡されるノートへの参照は呼び出し中のみ有効）
+ *  * `user_data` - `callback`にそのまま渡される任意のポインタ
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, for_each_todo_indexed};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::c_void;
+ *
+ *  extern "C" fn print_row(index: usize, id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) {
+ *  let style = if index % 2 == 0 { "even" } else { "odd" };
+ *  println!("[{}] {}: {}", style, id, note.to_str());
+ *  }
+ *
+ *  let app = App::default();
+ *  for_each_todo_indexed(&app, print_row, std::ptr::null_mut());
+ *  ```
+ */
+void
+for_each_todo_indexed (
+    App_t const * app,
+    void (*callback)(size_t, int32_t, char const *, void *),
+    void * user_data);
+
+/** \brief
+ *  Todoのスナップショットを作成してから順番に走査します
+ *
+ *  走査を始める前にIDとノートをローカルのバッファへコピーするため、
+ *  `callback`の中で元のアプリケーションにTodoを追加・変更しても安全です。
+ *  走査されるのは呼び出し時点のTodoのみで、走査中に追加されたTodoは含まれません。
+ *
+ *  `callback`の中で`app`を`&mut App`として変更できるように、`app`は参照ではなく
+ *  ポインタとして受け取り、スナップショットを作成した後は`app`を参照しません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへのポインタ（NULLの場合は何もしません）
+ *  * `callback` - 各Todoに対して呼び出される関数（渡されるノートへの参照は呼び出し中のみ有効）
+ *  * `user_data` - `callback`にそのまま渡される任意のポインタ
+ *
+ *  # Safety
+ *
+ *  `app`はNULLまたは有効な`App`を指している必要があります。呼び出し側は、この関数の
+ *  実行中に`app`への`&App`や`&mut App`を保持し続けてはいけません。`callback`の中で
+ *  変更する場合は、`app`と同じポインタから`&mut App`を作成してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, for_each_todo_snapshot};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::c_void;
+ *
+ *  extern "C" fn print_todo(id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) {
+ *  println!("{}: {}", id, note.to_str());
+ *  }
+ *
+ *  let app = App::default();
+ *  unsafe { for_each_todo_snapshot(&app, print_todo, std::ptr::null_mut()) };
+ *  ```
+ */
+void
+for_each_todo_snapshot (
+    App_t const * app,
+    void (*callback)(int32_t, char const *, void *),
+    void * user_data);
+
+/** \brief
+ *  Todoを保存順を変えずにIDでソートした順番で走査します
+ *
+ *  内部でソート済みのインデックス列を作成してから`callback`を呼び出すため、
+ *  `app.todos`の並び順は変更されません。同じIDのTodoは保存順で呼び出されます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `ascending` - `true`の場合は昇順、`false`の場合は降順
+ *  * `callback` - 各Todoに対して呼び出される関数（渡されるノートへの参照は呼び出し中のみ有効）
+ *  * `user_data` - `callback`にそのまま渡される任意のポインタ
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, for_each_todo_sorted_by_id};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::c_void;
+ *
+ *  extern "C" fn print_todo(id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) {
+ *  println!("{}: {}", id, note.to_str());
+ *  }
+ *
+ *  let app = App::default();
+ *  for_each_todo_sorted_by_id(&app, true, print_todo, std::ptr::null_mut());
+ *  ```
+ */
+void
+for_each_todo_sorted_by_id (
+    App_t const * app,
+    bool ascending,
+    void (*callback)(int32_t, char const *, void *),
+    void * user_data);
+
+/** \brief
+ *  Rust側で確保された文字列のメモリを解放します
+ *
+ *  `free_string`と同じ動作です。互換性のために残しています。
+ */
+void
+free_char_p_box (
+    char * _boxed);
+
+/** \brief
+ *  `i32`のベクタ（IDの一覧など）のメモリを解放します
+ *
+ *  # 引数
+ *
+ *  * `_vec` - 解放するベクタ
+ */
+void
+free_i32_vec (
+    Vec_int32_t _vec);
+
+/** \brief
+ *  [`Box`][`rust::Box`]`<[T]>` (fat pointer to a slice),
+ *  but with a guaranteed `#[repr(C)]` layout.
+ *
+ *  # C layout (for some given type T)
+ *
+ *  ```c
+ *  typedef struct {
+ *  // Cannot be NULL
+ *  T * ptr;
+ *  size_t len;
+ *  } slice_T;
+ *  ```
+ *
+ *  # Nullable pointer?
+ *
+ *  If you want to support the above typedef, but where the `ptr` field is
+ *  allowed to be `NULL` (with the contents of `len` then being undefined)
+ *  use the `Option< slice_ptr<_> >` type.
+ */
+typedef struct slice_boxed_int32 {
+    /** \brief
+     *  Pointer to the first element (if any).
+     */
+    int32_t * ptr;
+
+    /** \brief
+     *  Element count
+     */
+    size_t len;
+} slice_boxed_int32_t;
+
+/** \brief
+ *  `get_all_todo_ids`で取得したスライスのメモリを解放します
+ *
+ *  # 引数
+ *
+ *  * `_s` - 解放するスライス
+ */
+void
+free_id_slice (
+    slice_boxed_int32_t _s);
+
+/** \brief
+ *  Rust側で確保された文字列のメモリを解放します
+ *
+ *  `get_todo_note_at`など、`char_p::Box`を返す関数で受け取った文字列は
+ *  呼び出し側が所有権を持つため、使用後にこの関数で解放してください。
+ *
+ *  # 引数
+ *
+ *  * `_s` - 解放する文字列
+ *
+ *  # 使用例
+ *
+ *  ## Rust
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, free_string, get_todo_note_at};
+ *
+ *  let app = App::default();
+ *  let note = get_todo_note_at(&app, 0);
+ *  free_string(note);
+ *  ```
+ *
+ *  ## Go
+ *
+ *  ```go
+ *  cNote := C.get_todo_note_at(app, 0)
+ *  note := C.GoString(cNote)
+ *  C.free_string(cNote)
+ *  ```
+ */
+void
+free_string (
+    char * _s);
+
+/** \brief
+ *  `export_todo_c_array`で作成した配列を解放します
+ *
+ *  解放後は、配列およびその要素の`note_ptr`を参照しないでください。
+ *
+ *  # 引数
+ *
+ *  * `_handle` - 解放するハンドル
+ */
+void
+free_todo_c_array (
+    TodoCArrayHandle_t * _handle);
+
+/** \brief
+ *  `u8`のベクタ（バイト列）のメモリを解放します
+ *
+ *  # 引数
+ *
+ *  * `_vec` - 解放するベクタ
+ */
+void
+free_u8_vec (
+    Vec_uint8_t _vec);
+
+/** \brief
+ *  Same as [`Vec<T>`][`rust::Vec`], but with guaranteed `#[repr(C)]` layout
+ */
+typedef struct Vec_size {
+    /** <No documentation available> */
+    size_t * ptr;
+
+    /** <No documentation available> */
+    size_t len;
+
+    /** <No documentation available> */
+    size_t cap;
+} Vec_size_t;
+
+/** \brief
+ *  `usize`のベクタのメモリを解放します
+ *
+ *  # 引数
+ *
+ *  * `_vec` - 解放するベクタ
+ */
+void
+free_usize_vec (
+    Vec_size_t _vec);
+
+/** \brief
+ *  すべてのTodoのIDを1つのスライスとして取得します
+ *
+ *  `get_todo_id_at`をTodoの数だけ呼び出す代わりに、1回の呼び出しでIDの一覧を取得できます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  Todoの順番どおりのIDのスライスを返します。Todoが存在しない場合は長さ0のスライスを返し、
+ *  ポインタはNULLになりません。返されたスライスは`free_id_slice`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, free_id_slice, get_all_todo_ids};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut app, 3, char_p::Ref::from(note.as_ref()));
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let ids = get_all_todo_ids(&app);
+ *  assert_eq!(&ids[..], &[3, 1]);
+ *  free_id_slice(ids);
+ *  ```
+ */
+slice_boxed_int32_t
+get_all_todo_ids (
+    App_t const * app);
+
+/** \brief
+ *  指定インデックスのTodoの分類を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  分類のコピーを返します。未分類の場合やインデックスが範囲外の場合は空文字列を返します。
+ *  返された文字列は`free_string`で解放してください。
+ */
+char *
+get_category_at (
+    App_t const * app,
+    size_t index);
+
+/** \brief
+ *  指定インデックスのTodoの状態を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *  * `out_status` - 取得した状態の書き込み先
+ *
+ *  # 戻り値
+ *
+ *  成功した場合は`true`を返します。インデックスが範囲外の場合は`false`を返し、
+ *  `out_status`は変更されません。
+ */
+bool
+get_status_at (
+    App_t const * app,
+    size_t index,
+    Status_t * out_status);
+
+/** \brief
+ *  アプリケーション内のTodoの数を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  アプリケーション内のTodoアイテムの数
+ *
+ *  # 使用例
+ *
+ *  ## Rust
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_count};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  assert_eq!(get_todo_count(&app), 0);
+ *
+ *  // Todoを追加
+ *  let note = CString::new("タスク").unwrap();
+ *  let note_ref = char_p::Ref::from(note.as_ref());
+ *  add_todo(&mut app, 1, note_ref);
+ *
+ *  assert_eq!(get_todo_count(&app), 1);
+ *  ```
+ *
+ *  ## Go
+ *
+ *  ```go
+ *  import (
+ *  "example.com/todo"
+ *  "fmt"
+ *  )
+ *
+ *  func main() {
+ *  app := todo.AppNew()
+ *  defer todo.AppFree(app)
+ *
+ *  todo.AddTodo(app, 1, "重要なタスク")
+ *  count := todo.GetTodoCount(app)
+ *  fmt.Printf("Todo数: %d\n", count)
+ *  }
+ *  ```
+ */
+size_t
+get_todo_count (
+    App_t const * app);
+
+/** \brief
+ *  指定インデックスのTodoのIDを取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  成功した場合はTodoのID、インデックスが範囲外の場合は-1を返します。
+ *  IDが-1のTodoと区別する必要がある場合は`try_get_todo_id_at`を使用してください。
+ *
+ *  # 使用例
+ *
+ *  ## Rust
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_id_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *
+ *  // インデックスが範囲外の場合は-1を返す
+ *  assert_eq!(get_todo_id_at(&app, 0), -1);
+ *
+ *  // Todoを追加
+ *  let note = CString::new("タスク").unwrap();
+ *  let note_ref = char_p::Ref::from(note.as_ref());
+ *  add_todo(&mut app, 42, note_ref);
+ *
+ *  // 追加したTodoのIDを取得
+ *  assert_eq!(get_todo_id_at(&app, 0), 42);
+ *  ```
+ *
+ *  ## Go
+ *
+ *  ```go
+ *  import (
+ *  "example.com/todo"
+ *  "fmt"
+ *  )
+ *
+ *  func main() {
+ *  app := todo.AppNew()
+ *  defer todo.AppFree(app)
+ *
+ *  todo.AddTodo(app, 42, "重要なタスク")
+ *  id := todo.GetTodoIdAt(app, 0)
+ *  fmt.Printf("最初のTodoのID: %d\n", id)
+ *  }
+ *  ```
+ */
+int32_t
+get_todo_id_at (
+    App_t const * app,
+    size_t index);
+
+/** \brief
+ *  指定インデックスのTodoのノート（内容）を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  成功した場合はTodoのノート、インデックスが範囲外の場合は空文字列を返します
+ *
+ *  # 注意
+ *
+ *  返された文字列はRust側で確保されたメモリです。使用後は`free_string`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ## Rust
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_note_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *
+ *  // インデックスが範囲外の場合は空文字列を返す
+ *  let empty = get_todo_note_at(&app, 0);
+ *  assert_eq!(empty.to_str(), "");
+ *
+ *  // Todoを追加
+ *  let note = CString::new("重要なタスク").unwrap();
+ *  let note_ref = char_p::Ref::from(note.as_ref());
+ *  add_todo(&mut app, 1, note_ref);
+ *
+ *  // 追加したTodoのノートを取得
+ *  let retrieved = get_todo_note_at(&app, 0);
+ *  assert_eq!(retrieved.to_str(), "重要なタスク");
+ *  ```
+ *
+ *  ## Go
+ *
+ *  ```go
+ *  import (
+ *  "example.com/todo"
+ *  "fmt"
+ *  )
+ *
+ *  func main() {
+ *  app := todo.AppNew()
+ *  defer todo.AppFree(app)
+ *
+ *  todo.AddTodo(app, 1, "買い物リスト")
+ *  note := todo.GetTodoNoteAt(app, 0)
+ *  defer todo.FreeString(note)
+ *  fmt.Printf("Todo内容: %s\n", note)
+ *  }
+ *  ```
+ */
+char *
+get_todo_note_at (
+    App_t const * app,
+    size_t index);
+
+/** \brief
+ *  指定インデックスのTodoのノートを、呼び出し側が用意したバッファにコピーします
+ *
+ *  `get_todo_note_at`と異なりメモリを確保しないため、描画ループなどで繰り返し
+ *  ノートを読み出す場合に利用します。ノートのUTF-8バイト列とNUL終端を書き込み、
+ *  バッファが足りない場合は何も書き込みません。切り詰めてでも書き込みたい場合は
+ *  `copy_todo_note_truncated_at`を利用してください。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *  * `buf` - 書き込み先のバッファ（NUL終端の1バイトを含めた長さ）
+ *
+ *  # 戻り値
+ *
+ *  書き込んだバイト数（`copy_todo_note_truncated_at`と同じくNUL終端を含まない）を返します。
+ *  バッファがノートとNUL終端を格納するのに足りない場合は`-1`、インデックスが範囲外の場合は
+ *  `-2`を返し、いずれの場合もバッファは変更されません。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_note_at_into};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let mut buf = [0u8; 16];
+ *  assert_eq!(get_todo_note_at_into(&app, 0, (&mut buf[..]).into()), 6);
+ *  assert_eq!(&buf[..7], "牛乳\0".as_bytes());
+ *  ```
+ */
+ssize_t
+get_todo_note_at_into (
+    App_t const * app,
+    size_t index,
+    slice_mut_uint8_t buf);
+
+/** \brief
+ *  繰り返し利用できる文字列バッファ
+ *
+ *  `get_todo_note_into`でノートを書き込むと、必要に応じて容量を拡張しながら
+ *  同じメモリを使い回します。描画ループなどで`get_todo_note_at`を繰り返し呼び出し、
+ *  毎回`char_p::Box`を確保・解放するコストを避けたい場合に利用します。
+ */
+typedef struct StringBuf StringBuf_t;
+
+/** \brief
+ *  指定インデックスのTodoのノートを文字列バッファに書き込みます
+ *
+ *  バッファの既存の内容は上書きされます。容量が足りない場合のみ再確保が行われます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *  * `buf` - 書き込み先の文字列バッファ
+ *
+ *  # 戻り値
+ *
+ *  書き込みに成功した場合は`true`、インデックスが範囲外の場合は`false`を返します。
+ *  範囲外の場合、バッファの内容は変更されません。
+ */
+bool
+get_todo_note_into (
+    App_t const * app,
+    size_t index,
+    StringBuf_t * buf);
+
+/** \brief
+ *  指定したIDのTodoの優先度を取得します
+ *
+ *  同じIDのTodoが複数ある場合は、最初の1件の優先度を返します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `id` - 対象のTodoのID
+ *
+ *  # 戻り値
+ *
+ *  Todoの優先度を返します。該当するTodoが存在しない場合は既定値の`Priority::Medium`を
+ *  返すため、存在するかどうかを区別したい場合は`has_todo`と組み合わせてください。
+ */
+Priority_t
+get_todo_priority (
+    App_t const * app,
+    int32_t id);
+
+/** \brief
+ *  指定したIDのTodoが存在するかどうかを判定します
+ *
+ *  Todoを追加する前に、IDが既に使われているかを確認する場合に利用します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `id` - 確認するTodoのID
+ *
+ *  # 戻り値
+ *
+ *  同じIDのTodoが1件以上存在する場合は`true`、存在しない場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, has_todo};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  assert!(!has_todo(&app, 1));
+ *
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *  assert!(has_todo(&app, 1));
+ *  ```
+ */
+bool
+has_todo (
+    App_t const * app,
+    int32_t id);
+
+/** \brief
+ *  ノートが最も長いTodoのIDを取得します
+ *
+ *  ノートの長さはUTF-8のバイト数で比較します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  ノートが最も長いTodoのIDを返します。同じ長さの場合は先に出現したTodoのIDを返し、
+ *  Todoが存在しない場合は-1を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, id_of_longest_note};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  assert_eq!(id_of_longest_note(&app), -1);
+ *
+ *  for (id, note) in [(1, "短い"), (2, "とても長いノート")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *  assert_eq!(id_of_longest_note(&app), 2);
+ *  ```
+ */
+int32_t
+id_of_longest_note (
+    App_t const * app);
+
+/** \brief
+ *  指定した分類に属するTodoのIDを取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `category` - 検索する分類（完全一致）
+ *
+ *  # 戻り値
+ *
+ *  分類が一致するTodoのIDをリストの順番で返します。
+ *  空文字列を指定すると未分類のTodoのIDを返します。
+ *  返されたベクタは`free_i32_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, ids_in_category, set_category_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in [1, 2, 3] {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  let work = CString::new("仕事").unwrap();
+ *  set_category_at(&mut app, 0, char_p::Ref::from(work.as_ref()));
+ *  set_category_at(&mut app, 2, char_p::Ref::from(work.as_ref()));
+ *
+ *  assert_eq!(&ids_in_category(&app, char_p::Ref::from(work.as_ref()))[..], &[1, 3]);
+ *  ```
+ */
+Vec_int32_t
+ids_in_category (
+    App_t const * app,
+    char const * category);
+
+/** \brief
+ *  指定した範囲に含まれるIDの一覧を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `min_id` - 範囲の下限（この値を含む）
+ *  * `max_id` - 範囲の上限（この値を含む）
+ *
+ *  # 戻り値
+ *
+ *  `min_id <= id <= max_id`を満たすTodoのIDをリストの順番で返します。
+ *  `min_id > max_id`の場合は空のベクタを返します。
+ *  返されたベクタは`free_i32_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, ids_in_range};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in [5, 1, 3] {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(&ids_in_range(&app, 2, 5)[..], &[5, 3]);
+ *  ```
+ */
+Vec_int32_t
+ids_in_range (
+    App_t const * app,
+    int32_t min_id,
+    int32_t max_id);
+
+/** \brief
+ *  1つ目のアプリケーションにのみ存在するIDを取得します
+ *
+ *  # 引数
+ *
+ *  * `a` - 比較元のアプリケーションインスタンスへの参照
+ *  * `b` - 比較先のアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  `a`に存在し、`b`に存在しないIDを`a`の順番で返します。
+ *  `a`に同じIDのTodoが複数ある場合も1回だけ返します。
+ *  返されたベクタは`free_i32_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, ids_only_in_first};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let note = CString::new("タスク").unwrap();
+ *  let mut a = App::default();
+ *  let mut b = App::default();
+ *  for id in [1, 2, 3] {
+ *  add_todo(&mut a, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *  add_todo(&mut b, 2, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert_eq!(&ids_only_in_first(&a, &b)[..], &[1, 3]);
+ *  ```
+ */
+Vec_int32_t
+ids_only_in_first (
+    App_t const * a,
+    App_t const * b);
+
+/** \brief
+ *  2回以上登場するノートを持つTodoのIDを取得します
+ *
+ *  `distinct_note_count`を補完する関数です。重複したノートを持つTodoは、
+ *  最初の1件も含めてすべて結果に含まれます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  ノートがリスト内に2回以上登場するTodoのIDをリストの順番で返します。
+ *  重複がない場合は空のベクタを返します。
+ *  返されたベクタは`free_i32_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, ids_with_repeated_notes};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "牛乳"), (2, "卵"), (3, "牛乳")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(&ids_with_repeated_notes(&app)[..], &[1, 3]);
+ *  ```
+ */
+Vec_int32_t
+ids_with_repeated_notes (
+    App_t const * app);
+
+/** \brief
+ *  指定インデックスのTodoと同じノートを持つ、他のTodoのIDを取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 基準となるTodoのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  `index`のTodoとノートが完全に一致するTodoのIDをリストの順番で返します。
+ *  `index`のTodo自身は含まれません。インデックスが範囲外の場合は空のベクタを返します。
+ *  返されたベクタは`free_i32_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, ids_with_same_note_as};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "牛乳"), (2, "卵"), (3, "牛乳")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(&ids_with_same_note_as(&app, 0)[..], &[3]);
+ *  ```
+ */
+Vec_int32_t
+ids_with_same_note_as (
+    App_t const * app,
+    size_t index);
+
+/** \brief
+ *  ノートのバイト数が上限を超えているTodoのインデックスを取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `max_bytes` - ノートのUTF-8バイト数の上限
+ *
+ *  # 戻り値
+ *
+ *  ノートが`max_bytes`バイトを超えるTodoのインデックスを返します。
+ *  ちょうど`max_bytes`バイトのノートは含まれません。
+ *  返されたベクタは`free_usize_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, indices_over_byte_limit};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "abc"), (2, "abcdef")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(&indices_over_byte_limit(&app, 3)[..], &[1]);
+ *  ```
+ */
+Vec_size_t
+indices_over_byte_limit (
+    App_t const * app,
+    size_t max_bytes);
+
+/** \brief
+ *  IDの昇順を保つ位置にTodoを挿入します
+ *
+ *  Todoリストが既にIDの昇順に並んでいることを前提に、並び順を崩さない位置へ挿入します。
+ *  `add_todo`と同様に、同じIDのTodoが既に存在する場合は挿入しません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+ *  * `id` - 追加するTodoの一意識別子
+ *  * `note` - Todoの内容を表す文字列
+ *
+ *  # 戻り値
+ *
+ *  挿入した位置のインデックスを返します。挿入できなかった場合（IDが重複している場合や、
+ *  Todoの数が`set_max_todos`で設定した上限に達している場合を含む）は-1を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_id_at, insert_sorted_by_id};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *  add_todo(&mut app, 3, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert_eq!(insert_sorted_by_id(&mut app, 2, char_p::Ref::from(note.as_ref())), 1);
+ *  assert_eq!(get_todo_id_at(&app, 1), 2);
+ *
+ *  // 既に存在するIDは挿入できない
+ *  assert_eq!(insert_sorted_by_id(&mut app, 3, char_p::Ref::from(note.as_ref())), -1);
+ *  ```
+ */
+ssize_t
+insert_sorted_by_id (
+    App_t * app,
+    int32_t id,
+    char const * note);
+
+/** \brief
+ *  指定したIDのTodoが完了しているかどうかを判定します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `id` - 対象のTodoのID
+ *
+ *  # 戻り値
+ *
+ *  Todoが完了している場合は`true`を返します。
+ *  未完了の場合、または該当するTodoが存在しない場合は`false`を返します。
+ */
+bool
+is_todo_done (
+    App_t const * app,
+    int32_t id);
+
+/** \brief
+ *  ノートのバイト長の中央値を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  ノートのUTF-8バイト長の中央値を返します。Todoが偶数個の場合は中央の2つの平均を返します。
+ *  Todoが存在しない場合は`0.0`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, median_note_byte_len};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "a"), (2, "abcdef"), (3, "abc")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(median_note_byte_len(&app), 3.0);
+ *  ```
+ */
+double
+median_note_byte_len (
+    App_t const * app);
+
+/** \brief
+ *  期待するIDのうち、アプリケーションに存在しないものを取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `expected` - 存在するはずのIDの配列
+ *
+ *  # 戻り値
+ *
+ *  `expected`に含まれ、どのTodoのIDとも一致しないIDを`expected`の順番で返します。
+ *  `expected`に同じIDが複数回含まれる場合も1回だけ返します。
+ *  返されたベクタは`free_i32_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, missing_ids};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in [1, 2] {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(&missing_ids(&app, [1, 2, 3][..].into())[..], &[3]);
+ *  ```
+ */
+Vec_int32_t
+missing_ids (
+    App_t const * app,
+    slice_ref_int32_t expected);
+
+/** \brief
+ *  ノートの先頭の単語として最も多く使われている単語を取得します
+ *
+ *  各ノートの最初の空白区切りの単語を集計し、最も出現回数の多い単語を返します。
+ *  空白のみのノートは集計の対象外です。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  最も多く出現した先頭の単語を返します。同数の場合は先に出現した単語を返し、
+ *  集計対象のノートがない場合は空文字列を返します。
+ *  返された文字列は`free_string`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, most_common_first_word};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "買う 牛乳"), (2, "書く レポート"), (3, "買う 卵")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(most_common_first_word(&app).to_str(), "買う");
+ *  ```
+ */
+char *
+most_common_first_word (
+    App_t const * app);
+
+/** \brief
+ *  最も多く出現するIDとその出現回数を取得します
+ *
+ *  IDの重複を検出する用途を想定しています。出現回数が同じIDが複数ある場合は、
+ *  最も小さいIDを選びます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `out_id` - 最も多く出現するIDの書き込み先
+ *  * `out_count` - 出現回数の書き込み先
+ *
+ *  # 戻り値
+ *
+ *  成功した場合は`true`を返します。Todoが存在しない場合は`false`を返し、
+ *  `out_id`と`out_count`は変更されません。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Todo, most_frequent_id};
+ *
+ *  let mut app = App::default();
+ *  app.todos = vec![Todo::new(2, "a"), Todo::new(1, "b"), Todo::new(2, "c")].into();
+ *
+ *  let (mut id, mut count) = (0, 0);
+ *  assert!(most_frequent_id(&app, &mut id, &mut count));
+ *  assert_eq!((id, count), (2, 2));
+ *  ```
+ */
+bool
+most_frequent_id (
+    App_t const * app,
+    int32_t * out_id,
+    size_t * out_count);
+
+/** \brief
+ *  ノートのバイト長のヒストグラムを取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `bucket_size` - 1つの区間の幅（バイト数）
+ *
+ *  # 戻り値
+ *
+ *  `i`番目の要素に、バイト長が`[i * bucket_size, (i + 1) * bucket_size)`に含まれる
+ *  ノートの数を格納したベクタを返します。ベクタの長さは最も長いノートを含む区間までです。
+ *  `bucket_size`が0の場合やTodoが存在しない場合は空のベクタを返します。
+ *  返されたベクタは`free_usize_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, note_length_histogram};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "ab"), (2, "abcdef"), (3, "abc")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(&note_length_histogram(&app, 4)[..], &[2, 1]);
+ *  ```
+ */
+Vec_size_t
+note_length_histogram (
+    App_t const * app,
+    size_t bucket_size);
+
+/** \brief
+ *  完了しているTodoのうち、n番目（0から始まる）のインデックスを取得します
+ *
+ *  完了したTodoを順に移動する場合などに利用します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `n` - 完了しているTodoの中での順番（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  完了フラグが`true`のTodoのうち、リストの先頭から数えて`n`番目のTodoのインデックスを返します。
+ *  完了しているTodoが`n + 1`件未満の場合は`-1`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, nth_completed_index, set_todo_done};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in 1..=3 {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *  set_todo_done(&mut app, 2, true);
+ *
+ *  assert_eq!(nth_completed_index(&app, 0), 1);
+ *  assert_eq!(nth_completed_index(&app, 1), -1);
+ *  ```
+ */
+ssize_t
+nth_completed_index (
+    App_t const * app,
+    size_t n);
+
+/** \brief
+ *  すべてのTodoのIDに同じ値を加算します
+ *
+ *  取り込んだTodoのIDを別の範囲にずらして、既存のIDとの衝突を避ける場合に利用します。
+ *  加算結果が`i32`の範囲を超える場合は`i32::MIN`または`i32::MAX`に丸められます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `delta` - 加算する値（負の値も指定可能）
+ *
+ *  # 戻り値
+ *
+ *  範囲を超えて丸められたIDの数を返します。`0`以外の場合は、IDが重複している可能性があります。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_id_at, offset_all_ids};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert_eq!(offset_all_ids(&mut app, 1000), 0);
+ *  assert_eq!(get_todo_id_at(&app, 0), 1001);
+ *  ```
+ */
+size_t
+offset_all_ids (
+    App_t * app,
+    int32_t delta);
+
+/** \brief
+ *  すべてのTodoのノートを1つのバッファにまとめて取得します
+ *
+ *  ノートのUTF-8バイト列を連結して`out_bytes`に格納し、各ノートの開始オフセットを
+ *  `out_offsets`に格納します。`out_offsets`の末尾には全体の長さを表す番兵オフセットが
+ *  追加されるため、`i`番目のノートは`out_bytes[out_offsets[i]..out_offsets[i + 1]]`で
+ *  取り出せます。ノートはNUL終端されません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `out_offsets` - 各ノートの開始オフセットを書き込むベクタ（既存の内容は破棄されます）
+ *  * `out_bytes` - 連結したノートのバイト列を書き込むベクタ（既存の内容は破棄されます）
+ *
+ *  # 注意
+ *
+ *  書き込まれたベクタはそれぞれ`free_usize_vec`と`free_u8_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, pack_all_notes};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let mut offsets = repr_c::Vec::from(Vec::new());
+ *  let mut bytes = repr_c::Vec::from(Vec::new());
+ *  pack_all_notes(&app, &mut offsets, &mut bytes);
+ *
+ *  assert_eq!(&offsets[..], &[0, "牛乳".len()]);
+ *  assert_eq!(&bytes[..], "牛乳".as_bytes());
+ *  ```
+ */
+void
+pack_all_notes (
+    App_t const * app,
+    Vec_size_t * out_offsets,
+    Vec_uint8_t * out_bytes);
+
+/** \brief
+ *  シード値から決定的に選んだTodoのインデックスを取得します
+ *
+ *  復習用に1件を選ぶ場合など、再現性のある「ランダムな」選択に利用します。
+ *  内部ではSplitMix64で`seed`から擬似乱数を生成しています。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `seed` - 擬似乱数のシード値
+ *
+ *  # 戻り値
+ *
+ *  `0`以上Todoの数未満のインデックスを返します。同じシード値とTodoの数からは常に同じ
+ *  インデックスが得られます。Todoが存在しない場合は`-1`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, pick_index};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in 1..=5 {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  let index = pick_index(&app, 42);
+ *  assert!((0..5).contains(&index));
+ *  assert_eq!(pick_index(&app, 42), index);
+ *  ```
+ */
+ssize_t
+pick_index (
+    App_t const * app,
+    uint64_t seed);
+
+/** \brief
+ *  指定した優先度のTodoのうち、完了しているものの割合を取得します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `priority` - 集計する優先度
+ *
+ *  # 戻り値
+ *
+ *  指定した優先度のTodoに占める完了したTodoの割合（0.0〜1.0）を返します。
+ *  指定した優先度のTodoが存在しない場合は`0.0`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Priority, add_todo, priority_completion_rate, set_todo_done};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *  add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+ *  set_todo_done(&mut app, 1, true);
+ *
+ *  assert_eq!(priority_completion_rate(&app, Priority::Medium), 0.5);
+ *  assert_eq!(priority_completion_rate(&app, Priority::High), 0.0);
+ *  ```
+ */
+double
+priority_completion_rate (
+    App_t const * app,
+    Priority_t priority);
+
+/** \brief
+ *  指定したIDのTodoを削除します
+ *
+ *  同じIDのTodoが複数ある場合は、最初の1件のみを削除します。
+ *  残りのTodoの順番は変わりません。
+ *  削除した場合は、`set_on_change`で設定したコールバックを削除したIDで呼び出します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `id` - 削除するTodoのID
+ *
+ *  # 戻り値
+ *
+ *  削除した場合は`true`、該当するTodoが存在しない場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_count, remove_todo};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert!(remove_todo(&mut app, 1));
+ *  assert_eq!(get_todo_count(&app), 0);
+ *  assert!(!remove_todo(&mut app, 1));
+ *  ```
+ */
+bool
+remove_todo (
+    App_t * app,
+    int32_t id);
+
+/** \brief
+ *  グロブパターンに一致するノートをまとめて置き換えます
+ *
+ *  ノート全体がパターンに一致したTodoのノートを、`replacement`で丸ごと置き換えます。
+ *  パターンでは`*`が0文字以上の任意の文字列、`?`が任意の1文字に一致します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `pattern` - ノート全体と照合するグロブパターン
+ *  * `replacement` - 置き換え後のノート
+ *
+ *  # 戻り値
+ *
+ *  ノートを置き換えたTodoの数を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, replace_notes_matching_glob};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "temp: メモ"), (2, "牛乳を買う")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  let pattern = CString::new("temp*").unwrap();
+ *  let replacement = CString::new("(削除済み)").unwrap();
+ *  let replaced = replace_notes_matching_glob(
+ *  &mut app,
+ *  char_p::Ref::from(pattern.as_ref()),
+ *  char_p::Ref::from(replacement.as_ref()),
+ *  );
+ *
+ *  assert_eq!(replaced, 1);
+ *  assert_eq!(app.todos[0].note.to_str(), "(削除済み)");
+ *  ```
+ */
+size_t
+replace_notes_matching_glob (
+    App_t * app,
+    char const * pattern,
+    char const * replacement);
+
+/** \brief
+ *  指定した範囲のTodoの並び順を反転します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `start` - 範囲の開始インデックス（この値を含む）
+ *  * `end` - 範囲の終了インデックス（この値を含まない）
+ *
+ *  # 戻り値
+ *
+ *  反転に成功した場合は`true`を返します。
+ *  `start > end`または`end`がTodoの数を超える場合は何もせずに`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_id_at, reverse_range};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in 1..=4 {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert!(reverse_range(&mut app, 1, 3));
+ *  assert_eq!(get_todo_id_at(&app, 1), 3);
+ *  assert_eq!(get_todo_id_at(&app, 2), 2);
+ *  ```
+ */
+bool
+reverse_range (
+    App_t * app,
+    size_t start,
+    size_t end);
+
+/** \brief
+ *  複数のキーワードのいずれかを含むTodoを検索します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `needles` - 検索するキーワードの配列
+ *
+ *  # 戻り値
+ *
+ *  ノートにいずれかのキーワードを含むTodoのインデックスを昇順で返します。
+ *  複数のキーワードに一致したTodoも1回だけ含まれます。
+ *  `needles`が空の場合は何にも一致せず、空のベクタを返します。
+ *  返されたベクタは`free_usize_vec`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, search_any};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "牛乳を買う"), (2, "レポートを書く"), (3, "卵を買う")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  let milk = CString::new("牛乳").unwrap();
+ *  let egg = CString::new("卵").unwrap();
+ *  let needles = [char_p::Ref::from(milk.as_ref()), char_p::Ref::from(egg.as_ref())];
+ *
+ *  assert_eq!(&search_any(&app, needles[..].into())[..], &[0, 2]);
+ *  ```
+ */
+Vec_size_t
+search_any (
+    App_t const * app,
+    slice_ref_char_const_ptr_t needles);
+
+/** \brief
+ *  `&'lt [T]` but with a guaranteed `#[repr(C)]` layout.
+ *
+ *  # C layout (for some given type T)
+ *
+ *  ```c
+ *  typedef struct {
+ *  // Cannot be NULL
+ *  T * ptr;
+ *  size_t len;
+ *  } slice_T;
+ *  ```
+ *
+ *  # Nullable pointer?
+ *
+ *  If you want to support the above typedef, but where the `ptr` field is
+ *  allowed to be `NULL` (with the contents of `len` then being undefined)
+ *  use the `Option< slice_ptr<_> >` type.
+ */
+typedef struct slice_ref_size {
+    /** \brief
+     *  Pointer to the first element (if any).
+     */
+    size_t const * ptr;
+
+    /** \brief
+     *  Element count
+     */
+    size_t len;
+} slice_ref_size_t;
+
+/** \brief
+ *  パック形式のバッファからすべてのTodoのノートを一括で置き換えます
+ *
+ *  `pack_all_notes`と対になる関数です。`offsets`は各ノートの開始オフセットと
+ *  末尾の番兵オフセットからなり、`i`番目のTodoのノートは
+ *  `bytes[offsets[i]..offsets[i + 1]]`で与えられます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `offsets` - 各ノートの開始オフセットと番兵オフセット
+ *  * `bytes` - 連結したノートのバイト列
+ *
+ *  # 戻り値
+ *
+ *  置き換えに成功した場合は`true`を返します。
+ *  ノートの数（`offsets.len() - 1`）がTodoの数と一致しない場合、オフセットが不正な場合、
+ *  不正なUTF-8やNULバイトを含むノートがある場合は`false`を返し、アプリケーションは変更されません。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_note_at, set_all_notes_packed};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("古いノート").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let bytes = "新しいノート".as_bytes();
+ *  let offsets = [0, bytes.len()];
+ *  assert!(set_all_notes_packed(&mut app, offsets[..].into(), bytes.into()));
+ *  assert_eq!(get_todo_note_at(&app, 0).to_str(), "新しいノート");
+ *  ```
+ */
+bool
+set_all_notes_packed (
+    App_t * app,
+    slice_ref_size_t offsets,
+    slice_ref_uint8_t bytes);
+
+/** \brief
+ *  指定インデックスのTodoの分類を設定します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `index` - 対象のTodoのインデックス（0から始まる）
+ *  * `category` - 設定する分類（空文字列で未分類に戻ります）
+ *
+ *  # 戻り値
+ *
+ *  成功した場合は`true`、インデックスが範囲外の場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_category_at, set_category_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let category = CString::new("買い物").unwrap();
+ *  assert!(set_category_at(&mut app, 0, char_p::Ref::from(category.as_ref())));
+ *  assert_eq!(get_category_at(&app, 0).to_str(), "買い物");
+ *  ```
+ */
+bool
+set_category_at (
+    App_t * app,
+    size_t index,
+    char const * category);
+
+/** \brief
+ *  すべてのTodoのIDをリスト内の位置に合わせて振り直します
+ *
+ *  各Todoには`base + インデックス`のIDが割り当てられます。`i32::MAX`を超える場合は
+ *  `i32::MAX`で頭打ちになります。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `base` - 先頭のTodoに割り当てるID
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_id_at, set_ids_to_positions};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut app, 42, char_p::Ref::from(note.as_ref()));
+ *  add_todo(&mut app, 7, char_p::Ref::from(note.as_ref()));
+ *
+ *  set_ids_to_positions(&mut app, 1);
+ *  assert_eq!(get_todo_id_at(&app, 0), 1);
+ *  assert_eq!(get_todo_id_at(&app, 1), 2);
+ *  ```
+ */
+void
+set_ids_to_positions (
+    App_t * app,
+    int32_t base);
+
+/** \brief
+ *  追加できるTodoの最大数を設定します
+ *
+ *  上限に達している間は、`add_todo`、`add_todo_checked`、`add_todo_auto`、`add_todos`、
+ *  `insert_sorted_by_id`、`append_and_consume`など、Todoを追加する関数は追加を拒否します。
+ *  複数のTodoをまとめて追加する関数は、上限に達した時点で残りを追加しません。
+ *  `app_restore`は上限を超えるスナップショットの復元を拒否します。
+ *  既に上限を超えているTodoは削除されません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `max` - Todoの最大数（0の場合は無制限）
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, set_max_todos};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  set_max_todos(&mut app, 1);
+ *
+ *  let note = CString::new("タスク").unwrap();
+ *  assert!(add_todo(&mut app, 1, char_p::Ref::from(note.as_ref())));
+ *  assert!(!add_todo(&mut app, 2, char_p::Ref::from(note.as_ref())));
+ *  ```
+ */
+void
+set_max_todos (
+    App_t * app,
+    size_t max);
+
+/** \brief
+ *  長さ付きのバイト列から指定インデックスのTodoのノートを設定します
+ *
+ *  NUL終端文字列を扱わない言語から、文字列をコピーせずに渡す場合に利用します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `index` - 更新するTodoのインデックス（0から始まる）
+ *  * `bytes` - 新しいノートのUTF-8バイト列（NUL終端は不要）
+ *
+ *  # 戻り値
+ *
+ *  設定に成功した場合は`true`を返します。
+ *  インデックスが範囲外、バイト列が不正なUTF-8、またはNULを含む場合は`false`を返し、
+ *  Todoは変更されません。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, set_note_from_bytes_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert!(set_note_from_bytes_at(&mut app, 0, "卵を買う".as_bytes().into()));
+ *  assert_eq!(app.todos[0].note.to_str(), "卵を買う");
+ *  ```
+ */
+bool
+set_note_from_bytes_at (
+    App_t * app,
+    size_t index,
+    slice_ref_uint8_t bytes);
+
+/** \brief
+ *  Todoの追加・削除時に呼び出されるコールバックを設定します
+ *
+ *  リアクティブなUIなどで、変更に合わせて表示を更新したい場合に利用します。
+ *  コールバックは、Todoを追加・削除するすべての関数から、追加・削除されたTodoごとに
+ *  そのIDを引数として呼び出されます。`add_todos`や`clear_todos`などの一括で変更する関数では
+ *  Todoの数だけ呼び出され、`app_restore`と`tx_commit`では変更前との差分のIDだけが
+ *  呼び出されます。並び替えやノートの変更など、Todoの増減を伴わない変更では呼び出されません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `cb` - 呼び出すコールバック（NULLを渡すと設定を解除します）
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, set_on_change};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  extern "C" fn on_change(id: i32) {
+ *  println!("Todo {} が変更されました", id);
+ *  }
+ *
+ *  let mut app = App::default();
+ *  set_on_change(&mut app, Some(on_change));
+ *
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *  ```
+ */
+void
+set_on_change (
+    App_t * app,
+    void (*cb)(int32_t));
+
+/** \brief
+ *  複数のIDのTodoの優先度をまとめて設定します
+ *
+ *  `set_todo_priority`と異なり、同じIDのTodoが複数ある場合はそのすべてを更新します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `ids` - 優先度を設定するTodoのIDの配列（存在しないIDは無視されます）
+ *  * `priority` - 設定する優先度
+ *
+ *  # 戻り値
+ *
+ *  優先度を設定したTodoの数を返します。`ids`に同じIDが複数含まれていても、
+ *  1件のTodoは1回だけ数えます。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Priority, add_todo, get_todo_priority, set_priority_by_ids};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("タスク").unwrap();
+ *  for id in 1..=3 {
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(set_priority_by_ids(&mut app, [1, 3][..].into(), Priority::High), 2);
+ *  assert_eq!(get_todo_priority(&app, 2), Priority::Medium);
+ *  assert_eq!(get_todo_priority(&app, 3), Priority::High);
+ *  ```
+ */
+size_t
+set_priority_by_ids (
+    App_t * app,
+    slice_ref_int32_t ids,
+    Priority_t priority);
+
+/** \brief
+ *  指定インデックスのTodoの状態を設定します
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `index` - 対象のTodoのインデックス（0から始まる）
+ *  * `status` - 設定する状態
+ *
+ *  # 戻り値
+ *
+ *  成功した場合は`true`、インデックスが範囲外の場合は`false`を返します。
+ */
+bool
+set_status_at (
+    App_t * app,
+    size_t index,
+    Status_t status);
+
+/** \brief
+ *  指定したIDのTodoの完了フラグを設定します
+ *
+ *  完了にすると状態は`Status::Done`になり、未完了に戻すと`Status::Todo`になります。
+ *  同じIDのTodoが複数ある場合は、最初の1件のみを更新します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `id` - 対象のTodoのID
+ *  * `done` - 完了にする場合は`true`、未完了に戻す場合は`false`
+ *
+ *  # 戻り値
+ *
+ *  更新した場合は`true`、該当するTodoが存在しない場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, is_todo_done, set_todo_done};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert!(set_todo_done(&mut app, 1, true));
+ *  assert!(is_todo_done(&app, 1));
+ *  ```
+ */
+bool
+set_todo_done (
+    App_t * app,
+    int32_t id,
+    bool done);
+
+/** \brief
+ *  指定したIDのTodoの優先度を設定します
+ *
+ *  同じIDのTodoが複数ある場合は、最初の1件のみを更新します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `id` - 対象のTodoのID
+ *  * `priority` - 設定する優先度
+ *
+ *  # 戻り値
+ *
+ *  設定した場合は`true`、該当するTodoが存在しない場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Priority, add_todo, get_todo_priority, set_todo_priority};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert_eq!(get_todo_priority(&app, 1), Priority::Medium);
+ *  assert!(set_todo_priority(&mut app, 1, Priority::High));
+ *  assert_eq!(get_todo_priority(&app, 1), Priority::High);
+ *  ```
+ */
+bool
+set_todo_priority (
+    App_t * app,
+    int32_t id,
+    Priority_t priority);
+
+/** \brief
+ *  TodoをIDでソートします
+ *
+ *  `app.todos`の並び順そのものを変更します。安定ソートのため、同じIDのTodoは
+ *  ソート前の順番のまま並びます。並び順を変えずに走査したい場合は
+ *  `for_each_todo_sorted_by_id`を利用してください。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `ascending` - `true`の場合は昇順、`false`の場合は降順
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Todo, sort_todos_by_id};
+ *
+ *  let mut app = App::default();
+ *  app.todos = vec![Todo::new(3, "c"), Todo::new(1, "a"), Todo::new(2, "b")].into();
+ *
+ *  sort_todos_by_id(&mut app, true);
+ *  assert_eq!(app.todos.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+ *  ```
+ */
+void
+sort_todos_by_id (
+    App_t * app,
+    bool ascending);
+
+/** \brief
+ *  Todoをノートの内容でソートします
+ *
+ *  `app.todos`の並び順そのものを変更します。比較は`&str`の順序（UTF-8のバイト順、
+ *  すなわちUnicodeのコードポイント順）で行い、ロケールに応じた照合順序ではありません。
+ *  そのため大文字は小文字より前に並び、ひらがなはカタカナより前、漢字はさらに後ろに並びます。
+ *  安定ソートのため、同じノートのTodoはソート前の順番のまま並びます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `ascending` - `true`の場合は昇順、`false`の場合は降順
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Todo, sort_todos_by_note};
+ *
+ *  let mut app = App::default();
+ *  app.todos = vec![Todo::new(1, "りんご"), Todo::new(2, "banana"), Todo::new(3, "Apple")].into();
+ *
+ *  sort_todos_by_note(&mut app, true);
+ *  assert_eq!(app.todos.iter().map(|t| t.id).collect::<Vec<_>>(), vec![3, 2, 1]);
+ *  ```
+ */
+void
+sort_todos_by_note (
+    App_t * app,
+    bool ascending);
+
+/** \brief
+ *  文字列バッファのメモリを解放します
+ *
+ *  # 引数
+ *
+ *  * `_buf` - 解放する文字列バッファ
+ */
+void
+string_buf_free (
+    StringBuf_t * _buf);
+
+/** \brief
+ *  空の文字列バッファを作成します
+ *
+ *  # 戻り値
+ *
+ *  空文字列を保持するバッファを返します。`string_buf_free`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, get_todo_note_into, string_buf_new, string_buf_ptr};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let mut buf = string_buf_new();
+ *  assert!(get_todo_note_into(&app, 0, &mut buf));
+ *  assert_eq!(string_buf_ptr(&buf).to_str(), "牛乳を買う");
+ *  ```
+ */
+StringBuf_t *
+string_buf_new (void);
+
+/** \brief
+ *  文字列バッファの内容を取得します
+ *
+ *  # 引数
+ *
+ *  * `buf` - 文字列バッファへの参照
+ *
+ *  # 戻り値
+ *
+ *  バッファ内のNUL終端文字列への参照を返します。
+ *  参照はバッファに次の書き込みを行うか、バッファを解放するまで有効です。
+ */
+char const *
+string_buf_ptr (
+    StringBuf_t const * buf);
+
+/** \brief
+ *  2つのTodoのノートを入れ替えます
+ *
+ *  入れ替わるのはノートのみで、IDや状態は元の位置に残ります。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `a` - 入れ替えるTodoのインデックス
+ *  * `b` - 入れ替えるもう一方のTodoのインデックス
+ *
+ *  # 戻り値
+ *
+ *  入れ替えに成功した場合は`true`、いずれかのインデックスが範囲外の場合は`false`を返します。
+ *  `a`と`b`が同じ場合は何も変更せずに`true`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, swap_notes};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "牛乳"), (2, "卵")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert!(swap_notes(&mut app, 0, 1));
+ *  assert_eq!(app.todos[0].id, 1);
+ *  assert_eq!(app.todos[0].note.to_str(), "卵");
+ *  ```
+ */
+bool
+swap_notes (
+    App_t * app,
+    size_t a,
+    size_t b);
+
+/** \brief
+ *  複数のスレッドから同時に利用できるTodoアプリケーション
+ *
+ *  内部の`App`を`Mutex`で保護し、`sync_`で始まる関数は呼び出しごとにロックを取得します。
+ *  Goの複数のgoroutineから同じアプリケーションを操作する場合は、`App`と`add_todo`などの
+ *  代わりにこの型と`sync_`で始まる関数を利用してください。`App`を直接操作する関数は
+ *  同期を行わないため、同時に呼び出すとデータ競合が発生します。
+ */
+typedef struct SyncApp SyncApp_t;
+
+/** \brief
+ *  ロックを取得してTodoを追加します
+ *
+ *  `add_todo`の、複数のスレッドから同時に呼び出せる版です。
+ *
+ *  # 引数
+ *
+ *  * `app` - SyncAppインスタンスへの参照
+ *  * `id` - Todo項目の識別子
+ *  * `note` - Todoの内容を表す文字列への参照
+ *
+ *  # 戻り値
+ *
+ *  追加した場合は`true`、同じIDのTodoが既に存在する場合は`false`を返します。
+ */
+bool
+sync_add_todo (
+    SyncApp_t const * app,
+    int32_t id,
+    char const * note);
+
+/** \brief
+ *  SyncAppのメモリを解放します
+ *
+ *  他のスレッドが利用中のSyncAppを解放しないでください。
+ *
+ *  # 引数
+ *
+ *  * `_app` - 解放するSyncAppインスタンス
+ */
+void
+sync_app_free (
+    SyncApp_t * _app);
+
+/** \brief
+ *  複数のスレッドから利用できる空のアプリケーションを作成します
+ *
+ *  # 戻り値
+ *
+ *  新しいSyncAppインスタンスを返します。不要になったら`sync_app_free`で解放してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{sync_add_todo, sync_app_free, sync_app_new, sync_get_todo_count};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let app = sync_app_new();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *
+ *  std::thread::scope(|s| {
+ *  s.spawn(|| sync_add_todo(&app, 1, char_p::Ref::from(note.as_ref())));
+ *  s.spawn(|| sync_add_todo(&app, 2, char_p::Ref::from(note.as_ref())));
+ *  });
+ *
+ *  assert_eq!(sync_get_todo_count(&app), 2);
+ *  sync_app_free(app);
+ *  ```
+ */
+SyncApp_t *
+sync_app_new (void);
+
+/** \brief
+ *  ロックを取得してTodoの数を取得します
+ *
+ *  `get_todo_count`の、複数のスレッドから同時に呼び出せる版です。
+ *
+ *  # 引数
+ *
+ *  * `app` - SyncAppインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  Todoの数を返します。
+ */
+size_t
+sync_get_todo_count (
+    SyncApp_t const * app);
+
+/** \brief
+ *  指定インデックスのTodoの内容から、キャッシュ用の安定したハッシュ値を計算します
+ *
+ *  IDとノートのバイト列に対して固定のシードでFNV-1a（64ビット）を計算するため、
+ *  プロセスや実行環境が変わっても同じ内容からは同じ値が得られます。
+ *  状態、分類、優先度はハッシュに含まれません。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 対象のTodoのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  ハッシュ値を返します。インデックスが範囲外の場合は`0`を返しますが、
+ *  `0`は正常なハッシュ値としてもありえるため、範囲の判定には`get_todo_count`を併用してください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, todo_hash_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  let mut a = App::default();
+ *  let mut b = App::default();
+ *  add_todo(&mut a, 1, char_p::Ref::from(note.as_ref()));
+ *  add_todo(&mut b, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert_eq!(todo_hash_at(&a, 0), todo_hash_at(&b, 0));
+ *  ```
+ */
+uint64_t
+todo_hash_at (
+    App_t const * app,
+    size_t index);
+
+/** \brief
+ *  2つのアプリケーションの指定インデックスのTodoが等しいかどうかを判定します
+ *
+ *  IDとノートを含むすべてのフィールドを比較します。
+ *
+ *  # 引数
+ *
+ *  * `a` - 比較元のアプリケーションインスタンスへの参照
+ *  * `a_index` - `a`内のTodoのインデックス（0から始まる）
+ *  * `b` - 比較先のアプリケーションインスタンスへの参照
+ *  * `b_index` - `b`内のTodoのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  2つのTodoが等しい場合は`true`を返します。
+ *  異なる場合、またはいずれかのインデックスが範囲外の場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, todos_equal_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let note = CString::new("タスク").unwrap();
+ *  let mut a = App::default();
+ *  add_todo(&mut a, 1, char_p::Ref::from(note.as_ref()));
+ *  let b = a.clone();
+ *
+ *  assert!(todos_equal_at(&a, 0, &b, 0));
+ *  assert!(!todos_equal_at(&a, 0, &b, 1));
+ *  ```
+ */
+bool
+todos_equal_at (
+    App_t const * a,
+    size_t a_index,
+    App_t const * b,
+    size_t b_index);
+
+/** \brief
+ *  指定したIDのTodoの完了フラグを反転します
+ *
+ *  現在の状態を読み出してから逆の値を設定する代わりに、1回の呼び出しで切り替えます。
+ *  状態は`set_todo_done`と同様に、完了にすると`Status::Done`、未完了に戻すと`Status::Todo`になります。
+ *  同じIDのTodoが複数ある場合は、最初の1件のみを更新します。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `id` - 対象のTodoのID
+ *
+ *  # 戻り値
+ *
+ *  反転後の完了フラグを返します。該当するTodoが存在しない場合は何も変更せずに`false`を返すため、
+ *  存在するかどうかを区別したい場合は`has_todo`と組み合わせてください。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, toggle_todo_done};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  assert!(toggle_todo_done(&mut app, 1));
+ *  assert!(!toggle_todo_done(&mut app, 1));
+ *  ```
+ */
+bool
+toggle_todo_done (
+    App_t * app,
+    int32_t id);
+
+/** \brief
+ *  すべてのノートに含まれる単語の総数を取得します
+ *
+ *  単語は空白文字で区切って数えます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *
+ *  # 戻り値
+ *
+ *  各ノートの単語数の合計を返します。Todoが存在しない場合は0を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, total_word_count};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  for (id, note) in [(1, "buy some milk"), (2, "call mom")] {
+ *  let note = CString::new(note).unwrap();
+ *  add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+ *  }
+ *
+ *  assert_eq!(total_word_count(&app), 5);
+ *  ```
+ */
+size_t
+total_word_count (
+    App_t const * app);
+
+/** \brief
+ *  すべてのノートの前後の空白を取り除きます
+ *
+ *  空白の判定は`str::trim`と同じく、Unicodeの空白文字（全角スペースを含む）を対象とします。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *
+ *  # 戻り値
+ *
+ *  実際に内容が変わったノートの数を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, Todo, trim_all_notes};
+ *
+ *  let mut app = App::default();
+ *  app.todos = vec![Todo::new(1, "  牛乳を買う\n"), Todo::new(2, "レポート")].into();
+ *
+ *  assert_eq!(trim_all_notes(&mut app), 1);
+ *  assert_eq!(app.todos[0].note.to_str(), "牛乳を買う");
+ *  ```
+ */
+size_t
+trim_all_notes (
+    App_t * app);
+
+/** \brief
+ *  値が存在しない可能性のあるID
+ *
+ *  `has_value`が`false`の場合、`value`の内容は意味を持ちません。
+ */
+typedef struct OptionalId {
+    /** <No documentation available> */
+    bool has_value;
+
+    /** <No documentation available> */
+    int32_t value;
+} OptionalId_t;
+
+/** \brief
+ *  指定インデックスのTodoのIDを取得します
+ *
+ *  `get_todo_id_at`と異なり、IDが`-1`のTodoとインデックスが範囲外の場合を区別できます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの参照
+ *  * `index` - 取得するTodoのインデックス（0から始まる）
+ *
+ *  # 戻り値
+ *
+ *  成功した場合は`has_value`が`true`でTodoのIDを持つ`OptionalId`、
+ *  インデックスが範囲外の場合は`has_value`が`false`の`OptionalId`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, try_get_todo_id_at};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  assert!(!try_get_todo_id_at(&app, 0).has_value);
+ *
+ *  let note = CString::new("タスク").unwrap();
+ *  add_todo(&mut app, -1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let id = try_get_todo_id_at(&app, 0);
+ *  assert!(id.has_value);
+ *  assert_eq!(id.value, -1);
+ *  ```
+ */
+OptionalId_t
+try_get_todo_id_at (
+    App_t const * app,
+    size_t index);
+
+/** \brief
+ *  トランザクションの作業用アプリケーションを取得します
+ *
+ *  返されたAppに対して`add_todo`などの変更関数を呼び出すと、
+ *  その変更はトランザクション内にのみ適用されます。
+ *
+ *  # 引数
+ *
+ *  * `tx` - トランザクションへの可変参照
+ *
+ *  # 戻り値
+ *
+ *  作業用のAppへの可変参照を返します。トランザクションを解放した後は使用できません。
+ */
+App_t *
+tx_app (
+    Transaction_t * tx);
+
+/** \brief
+ *  トランザクション内の変更を元のアプリケーションに適用します
+ *
+ *  コミット後もトランザクションは引き続き利用でき、追加の変更を再度コミットできます。
+ *  元のアプリケーションに`on_change`が設定されている場合は、削除されたTodoのID、
+ *  追加されたTodoのIDの順に、差分のIDだけを通知します。
+ *  トランザクション内で追加して削除したTodoなど、差分に現れない変更は通知されません。
+ *  元のアプリケーションの`on_change`はコミット後もそのまま残ります。
+ *
+ *  # 引数
+ *
+ *  * `tx` - トランザクションへの可変参照
+ *
+ *  # Safety
+ *
+ *  `app_begin`に渡した元のアプリケーションが解放・移動されておらず、この関数の実行中に
+ *  他の参照から読み書きされていない必要があります。
+ */
+void
+tx_commit (
+    Transaction_t * tx);
+
+/** \brief
+ *  トランザクションのメモリを解放します
+ *
+ *  コミットされていない変更は破棄されます。
+ *
+ *  # 引数
+ *
+ *  * `_tx` - 解放するトランザクション
+ */
+void
+tx_free (
+    Transaction_t * _tx);
+
+/** \brief
+ *  トランザクション内の変更を破棄します
+ *
+ *  作業用のアプリケーションは元のアプリケーションの現在の状態に戻ります。
+ *  元のアプリケーションは変更されません。
+ *
+ *  # 引数
+ *
+ *  * `tx` - トランザクションへの可変参照
+ *
+ *  # Safety
+ *
+ *  `app_begin`に渡した元のアプリケーションが解放・移動されておらず、この関数の実行中に
+ *  変更されていない必要があります。
+ */
+void
+tx_rollback (
+    Transaction_t * tx);
+
+/** \brief
+ *  指定したIDのTodoのノートを更新します
+ *
+ *  同じIDのTodoが複数ある場合は、最初の1件のみを更新します。
+ *  古いノートは新しいノートに置き換えた時点で解放されます。
+ *
+ *  # 引数
+ *
+ *  * `app` - Todoアプリケーションインスタンスへの可変参照
+ *  * `id` - 更新するTodoのID
+ *  * `new_note` - 新しいノート
+ *
+ *  # 戻り値
+ *
+ *  更新した場合は`true`、該当するTodoが存在しない場合は`false`を返します。
+ *
+ *  # 使用例
+ *
+ *  ```rust
+ *  use safer_ffi_example::{App, add_todo, update_todo_note};
+ *  use safer_ffi::prelude::*;
+ *  use std::ffi::CString;
+ *
+ *  let mut app = App::default();
+ *  let note = CString::new("牛乳を買う").unwrap();
+ *  add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+ *
+ *  let new_note = CString::new("卵を買う").unwrap();
+ *  assert!(update_todo_note(&mut app, 1, char_p::Ref::from(new_note.as_ref())));
+ *  assert_eq!(app.todos[0].note.to_str(), "卵を買う");
+ *  ```
+ */
+bool
+update_todo_note (
+    App_t * app,
+    int32_t id,
+    char const * new_note);
 
 
 #ifdef __cplusplus
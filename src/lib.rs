@@ -1,4 +1,83 @@
 use safer_ffi::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::ffi::c_void;
+
+/// Todoの進行状態を表す列挙型
+///
+/// `Todo`（未着手）→`Doing`（作業中）→`Done`（完了）の3つの状態を持ちます。
+/// C側では`uint8_t`として扱われます。
+#[derive_ReprC]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Status {
+    /// 未着手
+    #[default]
+    Todo,
+    /// 作業中
+    Doing,
+    /// 完了
+    Done,
+}
+
+impl Status {
+    /// 次の状態を返します（`Todo`→`Doing`→`Done`→`Todo`）
+    pub fn next(self) -> Self {
+        match self {
+            Status::Todo => Status::Doing,
+            Status::Doing => Status::Done,
+            Status::Done => Status::Todo,
+        }
+    }
+
+    /// `#[repr(u8)]`の値から状態を復元します（範囲外の値の場合は`None`）
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Status::Todo),
+            1 => Some(Status::Doing),
+            2 => Some(Status::Done),
+            _ => None,
+        }
+    }
+}
+
+/// Todoの優先度を表す列挙型
+///
+/// `Low`（低）、`Medium`（中）、`High`（高）の3段階で、既定値は`Medium`です。
+/// C側では`uint8_t`として扱われます。
+#[derive_ReprC]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Priority {
+    /// 低
+    Low,
+    /// 中
+    #[default]
+    Medium,
+    /// 高
+    High,
+}
+
+impl Priority {
+    /// `#[repr(u8)]`の値から優先度を復元します（範囲外の値の場合は`None`）
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Priority::Low),
+            1 => Some(Priority::Medium),
+            2 => Some(Priority::High),
+            _ => None,
+        }
+    }
+}
 
 /// Todoアイテムを表す構造体
 ///
@@ -8,6 +87,15 @@ use safer_ffi::prelude::*;
 ///
 /// * `id` - Todo項目の一意識別子
 /// * `note` - Todo項目の内容を表す文字列（FFI互換のchar_p::Box型）
+/// * `status` - Todo項目の進行状態
+/// * `done` - Todo項目が完了しているかどうか（`status`が`Status::Done`の場合のみ`true`）
+/// * `category` - Todo項目の分類（未分類の場合は空文字列）
+/// * `priority` - Todo項目の優先度
+///
+/// # 注意
+///
+/// フィールドを追加するとC側の構造体のレイアウトが変わります。
+/// Rust側を更新した場合は、ヘッダーファイルを再生成してC/Go側も再ビルドしてください。
 ///
 /// # 使用例
 ///
@@ -21,10 +109,14 @@ use safer_ffi::prelude::*;
 /// ```
 #[derive_ReprC]
 #[repr(C)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Todo {
     pub id: i32,
     pub note: char_p::Box,
+    pub status: Status,
+    pub done: bool,
+    pub category: char_p::Box,
+    pub priority: Priority,
 }
 
 impl Todo {
@@ -37,7 +129,13 @@ impl Todo {
     ///
     /// # 戻り値
     ///
-    /// 初期化されたTodo構造体のインスタンス
+    /// 初期化されたTodo構造体のインスタンス（状態は`Status::Todo`、未完了、分類は空文字列、
+    /// 優先度は`Priority::Medium`）
+    ///
+    /// # パニック
+    ///
+    /// `note`がNULバイトを含む場合はパニックします。
+    /// 外部から受け取った文字列を扱う場合は`Todo::try_new`を使用してください。
     ///
     /// # 使用例
     ///
@@ -47,14 +145,67 @@ impl Todo {
     /// let todo = Todo::new(42, "重要なタスク");
     /// ```
     pub fn new(id: i32, note: &str) -> Self {
-        let c_string = std::ffi::CString::new(note).unwrap();
-        Self {
+        Self::try_new(id, note).unwrap()
+    }
+
+    /// 新しいTodoアイテムを作成します
+    ///
+    /// `Todo::new`と異なり、ノートがNULバイトを含む場合もパニックせずにエラーを返します。
+    ///
+    /// # 引数
+    ///
+    /// * `id` - Todo項目の一意識別子
+    /// * `note` - Todo項目の内容を表す文字列
+    ///
+    /// # 戻り値
+    ///
+    /// 初期化されたTodo構造体のインスタンスを返します。
+    /// `note`がNULバイトを含む場合は`NulError`を返します。
+    ///
+    /// # 使用例
+    ///
+    /// ```rust
+    /// use safer_ffi_example::Todo;
+    ///
+    /// assert!(Todo::try_new(1, "重要なタスク").is_ok());
+    /// assert!(Todo::try_new(2, "途中に\0を含む").is_err());
+    /// ```
+    pub fn try_new(id: i32, note: &str) -> Result<Self, std::ffi::NulError> {
+        let c_string = std::ffi::CString::new(note)?;
+        Ok(Self {
             id,
             note: char_p::Box::from(c_string),
+            status: Status::default(),
+            done: false,
+            category: char_p::Box::from(std::ffi::CString::default()),
+            priority: Priority::default(),
+        })
+    }
+
+    /// 状態を設定し、完了フラグを状態に合わせて更新します
+    fn set_status(&mut self, status: Status) {
+        self.status = status;
+        self.done = status == Status::Done;
+    }
+
+    /// 完了フラグを設定し、状態を完了フラグに合わせて更新します
+    ///
+    /// 未完了に戻す場合、状態は`Status::Todo`になります。
+    /// すでに未完了の場合は状態を変更しません。
+    fn set_done(&mut self, done: bool) {
+        if done {
+            self.set_status(Status::Done);
+        } else if self.done {
+            self.set_status(Status::Todo);
         }
     }
 }
 
+/// Todoが追加・削除されたときに呼び出されるコールバック
+///
+/// 引数には追加・削除されたTodoのIDが渡されます。C側ではNULLを渡すことで未設定を表します。
+pub type OnChangeCallback = Option<extern "C" fn(id: i32)>;
+
 /// Todoアプリケーションの状態を管理する構造体
 ///
 /// 複数のTodoアイテムを管理し、FFIを通じてC/Go言語からも利用可能です。
@@ -62,6 +213,14 @@ impl Todo {
 /// # フィールド
 ///
 /// * `todos` - Todo項目のコレクション（FFI互換のrepr_c::Vec型）
+/// * `next_id` - `add_todo_auto`で次に割り当てるID（初期値は1）
+/// * `on_change` - Todoの追加・削除時に呼び出されるコールバック（`set_on_change`で設定）
+/// * `max_todos` - 追加できるTodoの最大数（0の場合は無制限、`set_max_todos`で設定）
+///
+/// # 注意
+///
+/// フィールドを追加するとC側の構造体のレイアウトが変わります。
+/// C/Go側では`app_new`で作成したポインタを介してのみ扱い、フィールドに直接アクセスしないでください。
 ///
 /// # 使用例
 ///
@@ -85,14 +244,200 @@ impl Todo {
 #[derive(Debug, Clone)]
 pub struct App {
     pub todos: repr_c::Vec<Todo>,
+    pub next_id: i32,
+    pub on_change: OnChangeCallback,
+    pub max_todos: usize,
 }
 
 impl Default for App {
     fn default() -> Self {
         Self {
             todos: Vec::new().into(),
+            next_id: 1,
+            on_change: None,
+            max_todos: 0,
+        }
+    }
+}
+
+impl App {
+    /// 指定インデックスのTodoのノートを`String`として取得します
+    ///
+    /// `char_p`を扱わずにノートを読み出したいRust側の利用者向けの関数です。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - 取得するTodoのインデックス（0から始まる）
+    ///
+    /// # 戻り値
+    ///
+    /// ノートのコピーを返します。インデックスが範囲外の場合は`None`を返します。
+    ///
+    /// # 使用例
+    ///
+    /// ```rust
+    /// use safer_ffi_example::{App, Todo};
+    ///
+    /// let app = App {
+    ///     todos: vec![Todo::new(1, "牛乳を買う")].into(),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(app.note_at(0), Some("牛乳を買う".to_string()));
+    /// assert_eq!(app.note_at(1), None);
+    /// ```
+    pub fn note_at(&self, index: usize) -> Option<String> {
+        self.todos
+            .get(index)
+            .map(|todo| todo.note.to_str().to_string())
+    }
+}
+
+/// シリアライズ用のTodoの表現
+///
+/// `char_p::Box`は`serde`に対応していないため、Todoを一旦この構造体に変換してから
+/// シリアライズ・デシリアライズします。
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TodoRecord {
+    id: i32,
+    note: String,
+    #[serde(default)]
+    status: Status,
+    #[serde(default)]
+    done: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    category: String,
+    #[serde(default, skip_serializing_if = "is_default_priority")]
+    priority: Priority,
+}
+
+/// 優先度が既定値の場合はJSONに出力しないための判定関数
+#[cfg(feature = "serde")]
+fn is_default_priority(priority: &Priority) -> bool {
+    *priority == Priority::default()
+}
+
+#[cfg(feature = "serde")]
+impl TodoRecord {
+    fn from_todo(todo: &Todo) -> Self {
+        Self {
+            id: todo.id,
+            note: todo.note.to_str().to_string(),
+            status: todo.status,
+            done: todo.done,
+            category: todo.category.to_str().to_string(),
+            priority: todo.priority,
         }
     }
+
+    /// 正規化したJSONでの並び順を決めるキーを返します
+    ///
+    /// すべてのフィールドを含めるため、キーが等しいレコードはシリアライズ結果も等しくなります。
+    /// フィールドを追加した場合は、このキーにも追加してください。
+    fn sort_key(&self) -> (i32, &str, u8, bool, &str, u8) {
+        (
+            self.id,
+            &self.note,
+            self.status as u8,
+            self.done,
+            &self.category,
+            self.priority as u8,
+        )
+    }
+
+    /// Todoに変換します
+    ///
+    /// `done`が`true`の場合、状態は`status`に関わらず`Status::Done`になります。
+    /// ノートまたは分類にNULバイトが含まれる場合は`None`を返します。
+    fn into_todo(self) -> Option<Todo> {
+        let note = std::ffi::CString::new(self.note).ok()?;
+        let category = std::ffi::CString::new(self.category).ok()?;
+        let mut todo = Todo {
+            id: self.id,
+            note: char_p::Box::from(note),
+            status: Status::default(),
+            done: false,
+            category: char_p::Box::from(category),
+            priority: self.priority,
+        };
+        todo.set_status(if self.done { Status::Done } else { self.status });
+        Some(todo)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl App {
+    /// Todoをシリアライズ用の表現に変換します
+    fn to_records(&self) -> Vec<TodoRecord> {
+        self.todos.iter().map(TodoRecord::from_todo).collect()
+    }
+
+    /// シリアライズ用の表現からAppを組み立てます
+    ///
+    /// ノートにNULバイトが含まれる場合は`None`を返します。
+    fn from_records(records: Vec<TodoRecord>) -> Option<Self> {
+        let todos = records
+            .into_iter()
+            .map(TodoRecord::into_todo)
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            todos: todos.into(),
+            ..Default::default()
+        })
+    }
+}
+
+/// コールバックに渡すためにノートへの参照の寿命を呼び出し側の指定に合わせます
+///
+/// safer-ffiは高階の寿命を持つ関数ポインタ（`for<'a> extern "C" fn(char_p::Ref<'a>)`）を
+/// 扱えないため、ノートの所有者が関数内のローカル変数である場合にこの関数を使います。
+///
+/// # Safety
+///
+/// 返された参照は、元のノートが解放・変更されるまでの間（通常はコールバックの呼び出し中）
+/// のみ使用できます。
+unsafe fn note_ref_for_callback<'a>(note: &char_p::Box) -> char_p::Ref<'a> {
+    std::mem::transmute(note.as_ref())
+}
+
+/// 指定したIDを持つ最初のTodoのインデックスを取得します
+fn index_of(app: &App, id: i32) -> Option<usize> {
+    app.todos.iter().position(|todo| todo.id == id)
+}
+
+/// `max_todos`に達するまでに追加できるTodoの数を取得します
+///
+/// 上限が設定されていない場合は`usize::MAX`を返します。
+fn remaining_slots(app: &App) -> usize {
+    if app.max_todos == 0 {
+        return usize::MAX;
+    }
+    app.max_todos.saturating_sub(app.todos.len())
+}
+
+/// `on_change`が設定されている場合に、変更されたTodoのIDを渡して呼び出します
+fn notify_change(app: &App, id: i32) {
+    if let Some(on_change) = app.on_change {
+        on_change(id);
+    }
+}
+
+/// 変更前のTodoと比べて、削除されたTodoのID、追加されたTodoのIDの順に通知します
+fn notify_net_change(app: &App, before: &[Todo]) {
+    for todo in before
+        .iter()
+        .filter(|todo| index_of(app, todo.id).is_none())
+    {
+        notify_change(app, todo.id);
+    }
+    for todo in app
+        .todos
+        .iter()
+        .filter(|todo| !before.iter().any(|b| b.id == todo.id))
+    {
+        notify_change(app, todo.id);
+    }
 }
 
 /// 新しいAppインスタンスを作成します
@@ -127,8 +472,26 @@ pub fn app_new() -> repr_c::Box<App> {
     Box::new(App::default()).into()
 }
 
+/// Todoの追加結果
+#[derive_ReprC]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddResult {
+    /// 追加に成功した
+    Ok,
+    /// 同じIDのTodoが既に存在する
+    DuplicateId,
+    /// ノートがNULバイトを含むなど、Todoのノートとして扱えない
+    InvalidNote,
+    /// Todoの数が`max_todos`に達している
+    LimitReached,
+}
+
 /// Todoをアプリケーションに追加します
 ///
+/// `add_todo_checked`の結果を`bool`で返す薄いラッパーです。
+/// 失敗した理由を知りたい場合は`add_todo_checked`を使用してください。
+///
 /// # 引数
 ///
 /// * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
@@ -137,7 +500,7 @@ pub fn app_new() -> repr_c::Box<App> {
 ///
 /// # 戻り値
 ///
-/// 追加が成功した場合は`true`、失敗した場合は`false`を返します。
+/// 追加が成功した場合は`true`、失敗した場合（同じIDのTodoが既に存在する場合など）は`false`を返します。
 ///
 /// # 使用例
 ///
@@ -170,15 +533,67 @@ pub fn app_new() -> repr_c::Box<App> {
 /// ```
 #[ffi_export]
 pub fn add_todo(app: &mut App, id: i32, note: char_p::Ref<'_>) -> bool {
+    add_todo_checked(app, id, note) == AddResult::Ok
+}
+
+/// Todoをアプリケーションに追加し、結果を返します
+///
+/// 追加に成功した場合は、`set_on_change`で設定したコールバックを追加したIDで呼び出します。
+///
+/// # 引数
+///
+/// * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+/// * `id` - 追加するTodoの一意識別子
+/// * `note` - Todoの内容を表す文字列（FFI互換のchar_p::Ref型）
+///
+/// # 戻り値
+///
+/// 追加に成功した場合は`AddResult::Ok`を返します。
+/// 同じIDのTodoが既に存在する場合は`AddResult::DuplicateId`、
+/// ノートとして扱えない文字列の場合は`AddResult::InvalidNote`、
+/// Todoの数が`set_max_todos`で設定した上限に達している場合は`AddResult::LimitReached`を返し、
+/// Todoは追加されません。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{AddResult, App, add_todo_checked};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("重要なタスク").unwrap();
+///
+/// assert_eq!(add_todo_checked(&mut app, 1, char_p::Ref::from(note.as_ref())), AddResult::Ok);
+/// assert_eq!(
+///     add_todo_checked(&mut app, 1, char_p::Ref::from(note.as_ref())),
+///     AddResult::DuplicateId
+/// );
+/// ```
+#[ffi_export]
+pub fn add_todo_checked(app: &mut App, id: i32, note: char_p::Ref<'_>) -> AddResult {
     // 文字列をRustの文字列に変換
-    let note_str = note.to_str();
+    add_todo_str(app, id, note.to_str())
+}
+
+/// Rustの文字列をノートとしてTodoを追加します（`add_todo_checked`の本体）
+fn add_todo_str(app: &mut App, id: i32, note_str: &str) -> AddResult {
+    if index_of(app, id).is_some() {
+        return AddResult::DuplicateId;
+    }
+    if remaining_slots(app) == 0 {
+        return AddResult::LimitReached;
+    }
 
     // Todo構造体を作成
-    let todo = Todo::new(id, note_str);
+    let Ok(todo) = Todo::try_new(id, note_str) else {
+        return AddResult::InvalidNote;
+    };
 
     // repr_c::Vec から std::vec::Vec に変換
-    // Note: FFI互換のrepr_c::Vecから標準のVecに変換して操作する必要がある
-    let mut native_vec: Vec<Todo> = app.todos.iter().cloned().collect();
+    // Note: FFI互換のrepr_c::Vecから標準のVecに変換して操作する必要がある。
+    //       要素を複製しないよう、中身を取り出して所有権ごと移す
+    let mut native_vec: Vec<Todo> = std::mem::replace(&mut app.todos, Vec::new().into()).into();
 
     // 値を追加
     native_vec.push(todo);
@@ -186,7 +601,8 @@ pub fn add_todo(app: &mut App, id: i32, note: char_p::Ref<'_>) -> bool {
     // 再び repr_c::Vec に変換して設定
     app.todos = native_vec.into();
 
-    true
+    notify_change(app, id);
+    AddResult::Ok
 }
 
 /// アプリケーション内のTodoの数を取得します
@@ -250,7 +666,8 @@ pub fn get_todo_count(app: &App) -> usize {
 ///
 /// # 戻り値
 ///
-/// 成功した場合はTodoのID、インデックスが範囲外の場合は-1を返します
+/// 成功した場合はTodoのID、インデックスが範囲外の場合は-1を返します。
+/// IDが-1のTodoと区別する必要がある場合は`try_get_todo_id_at`を使用してください。
 ///
 /// # 使用例
 ///
@@ -294,13 +711,70 @@ pub fn get_todo_count(app: &App) -> usize {
 /// ```
 #[ffi_export]
 pub fn get_todo_id_at(app: &App, index: usize) -> i32 {
-    if index < app.todos.len() {
-        app.todos[index].id
+    let id = try_get_todo_id_at(app, index);
+    if id.has_value {
+        id.value
     } else {
         -1 // エラー値
     }
 }
 
+/// 値が存在しない可能性のあるID
+///
+/// `has_value`が`false`の場合、`value`の内容は意味を持ちません。
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionalId {
+    pub has_value: bool,
+    pub value: i32,
+}
+
+/// 指定インデックスのTodoのIDを取得します
+///
+/// `get_todo_id_at`と異なり、IDが`-1`のTodoとインデックスが範囲外の場合を区別できます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 取得するTodoのインデックス（0から始まる）
+///
+/// # 戻り値
+///
+/// 成功した場合は`has_value`が`true`でTodoのIDを持つ`OptionalId`、
+/// インデックスが範囲外の場合は`has_value`が`false`の`OptionalId`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, try_get_todo_id_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// assert!(!try_get_todo_id_at(&app, 0).has_value);
+///
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, -1, char_p::Ref::from(note.as_ref()));
+///
+/// let id = try_get_todo_id_at(&app, 0);
+/// assert!(id.has_value);
+/// assert_eq!(id.value, -1);
+/// ```
+#[ffi_export]
+pub fn try_get_todo_id_at(app: &App, index: usize) -> OptionalId {
+    match app.todos.get(index) {
+        Some(todo) => OptionalId {
+            has_value: true,
+            value: todo.id,
+        },
+        None => OptionalId {
+            has_value: false,
+            value: 0,
+        },
+    }
+}
+
 /// 指定インデックスのTodoのノート（内容）を取得します
 ///
 /// # 引数
@@ -312,6 +786,10 @@ pub fn get_todo_id_at(app: &App, index: usize) -> i32 {
 ///
 /// 成功した場合はTodoのノート、インデックスが範囲外の場合は空文字列を返します
 ///
+/// # 注意
+///
+/// 返された文字列はRust側で確保されたメモリです。使用後は`free_string`で解放してください。
+///
 /// # 使用例
 ///
 /// ## Rust
@@ -351,6 +829,7 @@ pub fn get_todo_id_at(app: &App, index: usize) -> i32 {
 ///
 ///     todo.AddTodo(app, 1, "買い物リスト")
 ///     note := todo.GetTodoNoteAt(app, 0)
+///     defer todo.FreeString(note)
 ///     fmt.Printf("Todo内容: %s\n", note)
 /// }
 /// ```
@@ -359,18 +838,107 @@ pub fn get_todo_note_at(app: &App, index: usize) -> char_p::Box {
     if index < app.todos.len() {
         // 文字列をコピーして返す
         let note_str = app.todos[index].note.to_str();
-        note_str.to_string().try_into().unwrap()
+        owned_string(note_str.to_string())
     } else {
         // エラーの場合は空文字列
-        "".to_string().try_into().unwrap()
+        owned_string(String::new())
     }
 }
 
+/// Rust側で確保された文字列のメモリを解放します
+///
+/// `get_todo_note_at`など、`char_p::Box`を返す関数で受け取った文字列は
+/// 呼び出し側が所有権を持つため、使用後にこの関数で解放してください。
+///
+/// # 引数
+///
+/// * `_s` - 解放する文字列
+///
+/// # 使用例
+///
+/// ## Rust
+///
+/// ```rust
+/// use safer_ffi_example::{App, free_string, get_todo_note_at};
+///
+/// let app = App::default();
+/// let note = get_todo_note_at(&app, 0);
+/// free_string(note);
+/// ```
+///
+/// ## Go
+///
+/// ```go
+/// cNote := C.get_todo_note_at(app, 0)
+/// note := C.GoString(cNote)
+/// C.free_string(cNote)
+/// ```
+#[ffi_export]
+pub fn free_string(_s: char_p::Box) {
+    // char_p::Box はドロップ時に自動的にメモリを解放します
+    untrack_string();
+}
+
+/// Rust側で確保された文字列のメモリを解放します
+///
+/// `free_string`と同じ動作です。互換性のために残しています。
 #[ffi_export]
 pub fn free_char_p_box(_boxed: char_p::Box) {
     // repr_c::Box はドロップ時に自動的にメモリを解放します
-    // この関数内で何もする必要はありません
     // boxed は関数終了時に自動的にドロップされます
+    untrack_string();
+}
+
+/// `leak-check`フィーチャーで数える、呼び出し側に渡したまま解放されていない文字列の数
+#[cfg(feature = "leak-check")]
+static LIVE_STRINGS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// 呼び出し側に所有権を渡す文字列を`leak-check`フィーチャーの計数に加えます
+fn track_string(s: char_p::Box) -> char_p::Box {
+    #[cfg(feature = "leak-check")]
+    LIVE_STRINGS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    s
+}
+
+/// `String`を呼び出し側に渡す`char_p::Box`へ変換します
+fn owned_string(s: String) -> char_p::Box {
+    track_string(s.try_into().unwrap())
+}
+
+/// `free_string`で解放された文字列を`leak-check`フィーチャーの計数から除きます
+fn untrack_string() {
+    // このクレートが返していない文字列を解放された場合でも0未満にはしない
+    #[cfg(feature = "leak-check")]
+    let _ = LIVE_STRINGS.fetch_update(
+        std::sync::atomic::Ordering::Relaxed,
+        std::sync::atomic::Ordering::Relaxed,
+        |count| count.checked_sub(1),
+    );
+}
+
+/// `get_todo_note_at`などが返し、まだ`free_string`で解放されていない文字列の数を返します
+///
+/// `leak-check`フィーチャーを有効にした場合のみ利用できます。
+/// 呼び出し側が文字列を解放し忘れていないかをテストで確認するために使います。
+///
+/// # 戻り値
+///
+/// 解放されていない文字列の数
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, free_string, get_todo_note_at, live_string_count};
+///
+/// let app = App::default();
+/// let note = get_todo_note_at(&app, 0);
+/// assert!(live_string_count() >= 1);
+/// free_string(note);
+/// ```
+#[cfg(feature = "leak-check")]
+#[ffi_export]
+pub fn live_string_count() -> usize {
+    LIVE_STRINGS.load(std::sync::atomic::Ordering::Relaxed)
 }
 
 /// アプリケーションのメモリを解放します
@@ -408,33 +976,4648 @@ pub fn app_free(_app: repr_c::Box<App>) {
     // app は関数終了時に自動的にドロップされます
 }
 
-/// FFIヘッダーファイルを生成します
+/// すべてのTodoのノートを1つのバッファにまとめて取得します
 ///
-/// このプロジェクトのRust関数とデータ構造をC/C++/Go等から利用するための
-/// ヘッダーファイルを生成します。ビルド時に`headers`機能が有効な場合のみ利用可能です。
+/// ノートのUTF-8バイト列を連結して`out_bytes`に格納し、各ノートの開始オフセットを
+/// `out_offsets`に格納します。`out_offsets`の末尾には全体の長さを表す番兵オフセットが
+/// 追加されるため、`i`番目のノートは`out_bytes[out_offsets[i]..out_offsets[i + 1]]`で
+/// 取り出せます。ノートはNUL終端されません。
 ///
-/// # 戻り値
+/// # 引数
 ///
-/// ヘッダーファイルの生成結果を表すResult
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `out_offsets` - 各ノートの開始オフセットを書き込むベクタ（既存の内容は破棄されます）
+/// * `out_bytes` - 連結したノートのバイト列を書き込むベクタ（既存の内容は破棄されます）
+///
+/// # 注意
+///
+/// 書き込まれたベクタはそれぞれ`free_usize_vec`と`free_u8_vec`で解放してください。
 ///
 /// # 使用例
 ///
-/// ```rust,no_run
-/// #[cfg(feature = "headers")]
-/// fn main() -> std::io::Result<()> {
-///     safer_ffi_example::generate_headers()
-/// }
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, pack_all_notes};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
 ///
-/// #[cfg(not(feature = "headers"))]
-/// fn main() {
-///     println!("headers機能が有効ではありません");
-/// }
+/// let mut app = App::default();
+/// let note = CString::new("牛乳").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let mut offsets = repr_c::Vec::from(Vec::new());
+/// let mut bytes = repr_c::Vec::from(Vec::new());
+/// pack_all_notes(&app, &mut offsets, &mut bytes);
+///
+/// assert_eq!(&offsets[..], &[0, "牛乳".len()]);
+/// assert_eq!(&bytes[..], "牛乳".as_bytes());
 /// ```
-#[cfg(feature = "headers")]
-pub fn generate_headers() -> ::std::io::Result<()> {
-    ::safer_ffi::headers::builder()
-        .to_file("./go_example/safer_ffi_example.h")?
-        .generate()
+#[ffi_export]
+pub fn pack_all_notes(
+    app: &App,
+    out_offsets: &mut repr_c::Vec<usize>,
+    out_bytes: &mut repr_c::Vec<u8>,
+) {
+    let mut offsets = Vec::with_capacity(app.todos.len() + 1);
+    let mut bytes = Vec::new();
+
+    for todo in app.todos.iter() {
+        offsets.push(bytes.len());
+        bytes.extend_from_slice(todo.note.to_str().as_bytes());
+    }
+    // 末尾に番兵オフセットを追加
+    offsets.push(bytes.len());
+
+    *out_offsets = offsets.into();
+    *out_bytes = bytes.into();
+}
+
+/// パック形式のバッファからすべてのTodoのノートを一括で置き換えます
+///
+/// `pack_all_notes`と対になる関数です。`offsets`は各ノートの開始オフセットと
+/// 末尾の番兵オフセットからなり、`i`番目のTodoのノートは
+/// `bytes[offsets[i]..offsets[i + 1]]`で与えられます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `offsets` - 各ノートの開始オフセットと番兵オフセット
+/// * `bytes` - 連結したノートのバイト列
+///
+/// # 戻り値
+///
+/// 置き換えに成功した場合は`true`を返します。
+/// ノートの数（`offsets.len() - 1`）がTodoの数と一致しない場合、オフセットが不正な場合、
+/// 不正なUTF-8やNULバイトを含むノートがある場合は`false`を返し、アプリケーションは変更されません。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_todo_note_at, set_all_notes_packed};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("古いノート").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let bytes = "新しいノート".as_bytes();
+/// let offsets = [0, bytes.len()];
+/// assert!(set_all_notes_packed(&mut app, offsets[..].into(), bytes.into()));
+/// assert_eq!(get_todo_note_at(&app, 0).to_str(), "新しいノート");
+/// ```
+#[ffi_export]
+pub fn set_all_notes_packed(
+    app: &mut App,
+    offsets: c_slice::Ref<'_, usize>,
+    bytes: c_slice::Ref<'_, u8>,
+) -> bool {
+    if offsets.is_empty() || offsets.len() - 1 != app.todos.len() {
+        return false;
+    }
+
+    // すべてのノートを検証してから置き換えることで、失敗時にアプリケーションを変更しない
+    let mut notes = Vec::with_capacity(app.todos.len());
+    for range in offsets.windows(2) {
+        let (start, end) = (range[0], range[1]);
+        if start > end || end > bytes.len() {
+            return false;
+        }
+        let Ok(note) = std::str::from_utf8(&bytes[start..end]) else {
+            return false;
+        };
+        let Ok(c_string) = std::ffi::CString::new(note) else {
+            return false;
+        };
+        notes.push(char_p::Box::from(c_string));
+    }
+
+    for (todo, note) in app.todos.iter_mut().zip(notes) {
+        todo.note = note;
+    }
+
+    true
+}
+
+/// `usize`のベクタのメモリを解放します
+///
+/// # 引数
+///
+/// * `_vec` - 解放するベクタ
+#[ffi_export]
+pub fn free_usize_vec(_vec: repr_c::Vec<usize>) {
+    // repr_c::Vec はドロップ時に自動的にメモリを解放します
+}
+
+/// `u8`のベクタ（バイト列）のメモリを解放します
+///
+/// # 引数
+///
+/// * `_vec` - 解放するベクタ
+#[ffi_export]
+pub fn free_u8_vec(_vec: repr_c::Vec<u8>) {
+    // repr_c::Vec はドロップ時に自動的にメモリを解放します
+}
+
+/// `i32`のベクタ（IDの一覧など）のメモリを解放します
+///
+/// # 引数
+///
+/// * `_vec` - 解放するベクタ
+#[ffi_export]
+pub fn free_i32_vec(_vec: repr_c::Vec<i32>) {
+    // repr_c::Vec はドロップ時に自動的にメモリを解放します
+}
+
+/// 重複したIDとその出現インデックスの組
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct DuplicateIdPair {
+    id: i32,
+    indices: Vec<usize>,
+}
+
+/// 複数回出現するIDと、そのIDを持つTodoのインデックスをJSONで取得します
+///
+/// `serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// `[{"id":1,"indices":[0,2]}]`の形式のJSON配列を返します。
+/// 要素はIDが最初に出現した順に並び、重複がない場合は`[]`を返します。
+/// 返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, duplicate_id_pairs};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(duplicate_id_pairs(&app).to_str(), "[]");
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn duplicate_id_pairs(app: &App) -> char_p::Box {
+    // IDの初出順を保ったまま出現インデックスを集める
+    let mut pairs: Vec<DuplicateIdPair> = Vec::new();
+    for (index, todo) in app.todos.iter().enumerate() {
+        match pairs.iter_mut().find(|pair| pair.id == todo.id) {
+            Some(pair) => pair.indices.push(index),
+            None => pairs.push(DuplicateIdPair {
+                id: todo.id,
+                indices: vec![index],
+            }),
+        }
+    }
+    pairs.retain(|pair| pair.indices.len() > 1);
+
+    owned_string(serde_json::to_string(&pairs).unwrap())
+}
+
+/// アプリケーションをシリアライズしてdeflate圧縮したバイト列に変換します
+///
+/// 長いノートを多く保持する場合に、保存・転送サイズを抑えるために利用します。
+/// `compress`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 圧縮したバイト列を返します。`free_u8_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, app_from_compressed_bytes, app_to_compressed_bytes};
+///
+/// let app = App::default();
+/// let bytes = app_to_compressed_bytes(&app);
+/// let restored = app_from_compressed_bytes(bytes.as_ref());
+/// assert_eq!(restored.todos.len(), 0);
+/// ```
+#[cfg(feature = "compress")]
+#[ffi_export]
+pub fn app_to_compressed_bytes(app: &App) -> repr_c::Vec<u8> {
+    use std::io::Write;
+
+    let json = serde_json::to_vec(&app.to_records()).unwrap();
+    let mut encoder =
+        flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+    // Vecへの書き込みは失敗しない
+    encoder.write_all(&json).unwrap();
+    encoder.finish().unwrap().into()
+}
+
+/// `app_to_compressed_bytes`で圧縮したバイト列からアプリケーションを復元します
+///
+/// `compress`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `data` - `app_to_compressed_bytes`で生成したバイト列
+///
+/// # 戻り値
+///
+/// 復元したAppのインスタンスを返します。展開やデシリアライズに失敗した場合は空のAppを返します。
+/// 返されたAppは`app_free`で解放してください。
+#[cfg(feature = "compress")]
+#[ffi_export]
+pub fn app_from_compressed_bytes(data: c_slice::Ref<'_, u8>) -> repr_c::Box<App> {
+    use std::io::Read;
+
+    let mut json = Vec::new();
+    let app = flate2::read::DeflateDecoder::new(data.as_slice())
+        .read_to_end(&mut json)
+        .ok()
+        .and_then(|_| serde_json::from_slice(&json).ok())
+        .and_then(App::from_records)
+        .unwrap_or_default();
+
+    Box::new(app).into()
+}
+
+/// Todoを保存順を変えずにIDでソートした順番で走査します
+///
+/// 内部でソート済みのインデックス列を作成してから`callback`を呼び出すため、
+/// `app.todos`の並び順は変更されません。同じIDのTodoは保存順で呼び出されます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `ascending` - `true`の場合は昇順、`false`の場合は降順
+/// * `callback` - 各Todoに対して呼び出される関数（渡されるノートへの参照は呼び出し中のみ有効）
+/// * `user_data` - `callback`にそのまま渡される任意のポインタ
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, for_each_todo_sorted_by_id};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::c_void;
+///
+/// extern "C" fn print_todo(id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) {
+///     println!("{}: {}", id, note.to_str());
+/// }
+///
+/// let app = App::default();
+/// for_each_todo_sorted_by_id(&app, true, print_todo, std::ptr::null_mut());
+/// ```
+#[ffi_export]
+pub fn for_each_todo_sorted_by_id<'app>(
+    app: &'app App,
+    ascending: bool,
+    callback: extern "C" fn(id: i32, note: char_p::Ref<'app>, user_data: *mut c_void),
+    user_data: *mut c_void,
+) {
+    let mut order: Vec<usize> = (0..app.todos.len()).collect();
+    // 安定ソートなので同じIDのTodoは保存順のまま
+    if ascending {
+        order.sort_by_key(|&i| app.todos[i].id);
+    } else {
+        order.sort_by_key(|&i| std::cmp::Reverse(app.todos[i].id));
+    }
+
+    for i in order {
+        let todo = &app.todos[i];
+        callback(todo.id, todo.note.as_ref(), user_data);
+    }
+}
+
+/// 複数の変更をまとめて適用・破棄するためのトランザクション
+///
+/// `app_begin`で作成した時点のアプリケーションのコピーを作業用に保持します。
+/// 作業用のAppは`tx_app`で取得でき、`add_todo`などの通常の変更関数をそのまま適用できます。
+/// 変更は`tx_commit`を呼び出すまで元のアプリケーションには反映されません。
+///
+/// 作業用のAppには`on_change`を引き継がないため、トランザクション内の変更では
+/// コールバックは呼び出されません。コミット時に差分の分だけまとめて呼び出されます。
+///
+/// # 注意
+///
+/// トランザクションは元のアプリケーションへのポインタを保持します。
+/// 元のアプリケーションを参照する`tx_commit`と`tx_rollback`は`unsafe`で、
+/// 呼び出し側が元のアプリケーションの生存と排他的なアクセスを保証する必要があります。
+#[derive_ReprC]
+#[repr(opaque)]
+pub struct Transaction {
+    target: *mut App,
+    working: App,
+}
+
+/// アプリケーションに対するトランザクションを開始します
+///
+/// # 引数
+///
+/// * `app` - トランザクションの対象となるアプリケーションインスタンスへの可変参照
+///
+/// # 戻り値
+///
+/// 現在の状態をコピーしたトランザクションを返します。`tx_free`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, app_begin, tx_app, tx_commit, tx_free};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let mut tx = app_begin(&mut app);
+///
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(tx_app(&mut tx), 1, char_p::Ref::from(note.as_ref()));
+/// // Safety: `app`はトランザクションの解放まで生存し、他からは参照されていない
+/// unsafe { tx_commit(&mut tx) };
+/// tx_free(tx);
+///
+/// assert_eq!(app.todos.len(), 1);
+/// ```
+#[ffi_export]
+pub fn app_begin(app: &mut App) -> repr_c::Box<Transaction> {
+    Box::new(Transaction {
+        working: working_copy(app),
+        target: app,
+    })
+    .into()
+}
+
+/// トランザクションの作業用に、`on_change`を外したアプリケーションのコピーを作成します
+fn working_copy(app: &App) -> App {
+    App {
+        on_change: None,
+        ..app.clone()
+    }
+}
+
+/// トランザクションの作業用アプリケーションを取得します
+///
+/// 返されたAppに対して`add_todo`などの変更関数を呼び出すと、
+/// その変更はトランザクション内にのみ適用されます。
+///
+/// # 引数
+///
+/// * `tx` - トランザクションへの可変参照
+///
+/// # 戻り値
+///
+/// 作業用のAppへの可変参照を返します。トランザクションを解放した後は使用できません。
+#[ffi_export]
+pub fn tx_app(tx: &mut Transaction) -> &mut App {
+    &mut tx.working
+}
+
+/// トランザクション内の変更を元のアプリケーションに適用します
+///
+/// コミット後もトランザクションは引き続き利用でき、追加の変更を再度コミットできます。
+/// 元のアプリケーションに`on_change`が設定されている場合は、削除されたTodoのID、
+/// 追加されたTodoのIDの順に、差分のIDだけを通知します。
+/// トランザクション内で追加して削除したTodoなど、差分に現れない変更は通知されません。
+/// 元のアプリケーションの`on_change`はコミット後もそのまま残ります。
+///
+/// # 引数
+///
+/// * `tx` - トランザクションへの可変参照
+///
+/// # Safety
+///
+/// `app_begin`に渡した元のアプリケーションが解放・移動されておらず、この関数の実行中に
+/// 他の参照から読み書きされていない必要があります。
+#[ffi_export]
+pub unsafe fn tx_commit(tx: &mut Transaction) {
+    let target = unsafe { &mut *tx.target };
+    let committed = App {
+        on_change: target.on_change,
+        ..tx.working.clone()
+    };
+    let before = std::mem::replace(target, committed);
+    notify_net_change(target, &before.todos);
+}
+
+/// トランザクション内の変更を破棄します
+///
+/// 作業用のアプリケーションは元のアプリケーションの現在の状態に戻ります。
+/// 元のアプリケーションは変更されません。
+///
+/// # 引数
+///
+/// * `tx` - トランザクションへの可変参照
+///
+/// # Safety
+///
+/// `app_begin`に渡した元のアプリケーションが解放・移動されておらず、この関数の実行中に
+/// 変更されていない必要があります。
+#[ffi_export]
+pub unsafe fn tx_rollback(tx: &mut Transaction) {
+    tx.working = working_copy(unsafe { &*tx.target });
+}
+
+/// トランザクションのメモリを解放します
+///
+/// コミットされていない変更は破棄されます。
+///
+/// # 引数
+///
+/// * `_tx` - 解放するトランザクション
+#[ffi_export]
+pub fn tx_free(_tx: repr_c::Box<Transaction>) {
+    // repr_c::Box はドロップ時に自動的にメモリを解放します
+}
+
+/// ノートの先頭の単語として最も多く使われている単語を取得します
+///
+/// 各ノートの最初の空白区切りの単語を集計し、最も出現回数の多い単語を返します。
+/// 空白のみのノートは集計の対象外です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 最も多く出現した先頭の単語を返します。同数の場合は先に出現した単語を返し、
+/// 集計対象のノートがない場合は空文字列を返します。
+/// 返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, most_common_first_word};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "買う 牛乳"), (2, "書く レポート"), (3, "買う 卵")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(most_common_first_word(&app).to_str(), "買う");
+/// ```
+#[ffi_export]
+pub fn most_common_first_word(app: &App) -> char_p::Box {
+    // 初出順を保ったまま出現回数を数える
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for todo in app.todos.iter() {
+        let Some(word) = todo.note.to_str().split_whitespace().next() else {
+            continue;
+        };
+        match counts.iter_mut().find(|(w, _)| *w == word) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((word, 1)),
+        }
+    }
+
+    // 同数の場合は先に出現した単語を優先する
+    let mut best: Option<(&str, usize)> = None;
+    for &(word, count) in &counts {
+        if best.is_none_or(|(_, best_count)| count > best_count) {
+            best = Some((word, count));
+        }
+    }
+
+    let word = best.map(|(word, _)| word).unwrap_or("");
+    owned_string(word.to_string())
+}
+
+/// 指定した範囲に含まれるIDの一覧を取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `min_id` - 範囲の下限（この値を含む）
+/// * `max_id` - 範囲の上限（この値を含む）
+///
+/// # 戻り値
+///
+/// `min_id <= id <= max_id`を満たすTodoのIDをリストの順番で返します。
+/// `min_id > max_id`の場合は空のベクタを返します。
+/// 返されたベクタは`free_i32_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, ids_in_range};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in [5, 1, 3] {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(&ids_in_range(&app, 2, 5)[..], &[5, 3]);
+/// ```
+#[ffi_export]
+pub fn ids_in_range(app: &App, min_id: i32, max_id: i32) -> repr_c::Vec<i32> {
+    app.todos
+        .iter()
+        .map(|todo| todo.id)
+        .filter(|id| (min_id..=max_id).contains(id))
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// 2つのアプリケーションの指定インデックスのTodoが等しいかどうかを判定します
+///
+/// IDとノートを含むすべてのフィールドを比較します。
+///
+/// # 引数
+///
+/// * `a` - 比較元のアプリケーションインスタンスへの参照
+/// * `a_index` - `a`内のTodoのインデックス（0から始まる）
+/// * `b` - 比較先のアプリケーションインスタンスへの参照
+/// * `b_index` - `b`内のTodoのインデックス（0から始まる）
+///
+/// # 戻り値
+///
+/// 2つのTodoが等しい場合は`true`を返します。
+/// 異なる場合、またはいずれかのインデックスが範囲外の場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, todos_equal_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let note = CString::new("タスク").unwrap();
+/// let mut a = App::default();
+/// add_todo(&mut a, 1, char_p::Ref::from(note.as_ref()));
+/// let b = a.clone();
+///
+/// assert!(todos_equal_at(&a, 0, &b, 0));
+/// assert!(!todos_equal_at(&a, 0, &b, 1));
+/// ```
+#[ffi_export]
+pub fn todos_equal_at(a: &App, a_index: usize, b: &App, b_index: usize) -> bool {
+    match (a.todos.get(a_index), b.todos.get(b_index)) {
+        (Some(a_todo), Some(b_todo)) => a_todo == b_todo,
+        _ => false,
+    }
+}
+
+/// タブ区切り（TSV）のテキストからアプリケーションを作成します
+///
+/// 各行を`id<TAB>note`として解釈します。行末の`\r`は取り除かれるため、
+/// CRLF改行のテキストもそのまま読み込めます。
+///
+/// # 引数
+///
+/// * `text` - 読み込むTSVテキスト
+///
+/// # 戻り値
+///
+/// 読み込んだTodoを持つAppのインスタンスを返します。
+/// タブを含まない行やIDを整数として解釈できない行は読み飛ばされます。
+/// 返されたAppは`app_free`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::app_from_tsv;
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let text = CString::new("1\t牛乳を買う\n2\tレポートを書く\n").unwrap();
+/// let app = app_from_tsv(char_p::Ref::from(text.as_ref()));
+///
+/// assert_eq!(app.todos.len(), 2);
+/// assert_eq!(app.todos[1].note.to_str(), "レポートを書く");
+/// ```
+#[ffi_export]
+pub fn app_from_tsv(text: char_p::Ref<'_>) -> repr_c::Box<App> {
+    let todos: Vec<Todo> = text
+        .to_str()
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_end_matches('\r');
+            let (id, note) = line.split_once('\t')?;
+            let id = id.parse().ok()?;
+            Todo::try_new(id, note).ok()
+        })
+        .collect();
+
+    Box::new(App {
+        todos: todos.into(),
+        ..Default::default()
+    })
+    .into()
+}
+
+/// ノートのバイト数が上限を超えているTodoのインデックスを取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `max_bytes` - ノートのUTF-8バイト数の上限
+///
+/// # 戻り値
+///
+/// ノートが`max_bytes`バイトを超えるTodoのインデックスを返します。
+/// ちょうど`max_bytes`バイトのノートは含まれません。
+/// 返されたベクタは`free_usize_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, indices_over_byte_limit};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "abc"), (2, "abcdef")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(&indices_over_byte_limit(&app, 3)[..], &[1]);
+/// ```
+#[ffi_export]
+pub fn indices_over_byte_limit(app: &App, max_bytes: usize) -> repr_c::Vec<usize> {
+    app.todos
+        .iter()
+        .enumerate()
+        .filter(|(_, todo)| todo.note.to_str().len() > max_bytes)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// 繰り返し利用できる文字列バッファ
+///
+/// `get_todo_note_into`でノートを書き込むと、必要に応じて容量を拡張しながら
+/// 同じメモリを使い回します。描画ループなどで`get_todo_note_at`を繰り返し呼び出し、
+/// 毎回`char_p::Box`を確保・解放するコストを避けたい場合に利用します。
+#[derive_ReprC]
+#[repr(opaque)]
+pub struct StringBuf {
+    // 常にNUL終端されたUTF-8バイト列を保持する
+    bytes: Vec<u8>,
+}
+
+/// 空の文字列バッファを作成します
+///
+/// # 戻り値
+///
+/// 空文字列を保持するバッファを返します。`string_buf_free`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_todo_note_into, string_buf_new, string_buf_ptr};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let mut buf = string_buf_new();
+/// assert!(get_todo_note_into(&app, 0, &mut buf));
+/// assert_eq!(string_buf_ptr(&buf).to_str(), "牛乳を買う");
+/// ```
+#[ffi_export]
+pub fn string_buf_new() -> repr_c::Box<StringBuf> {
+    Box::new(StringBuf { bytes: vec![0] }).into()
+}
+
+/// 指定インデックスのTodoのノートを文字列バッファに書き込みます
+///
+/// バッファの既存の内容は上書きされます。容量が足りない場合のみ再確保が行われます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 取得するTodoのインデックス（0から始まる）
+/// * `buf` - 書き込み先の文字列バッファ
+///
+/// # 戻り値
+///
+/// 書き込みに成功した場合は`true`、インデックスが範囲外の場合は`false`を返します。
+/// 範囲外の場合、バッファの内容は変更されません。
+#[ffi_export]
+pub fn get_todo_note_into(app: &App, index: usize, buf: &mut StringBuf) -> bool {
+    let Some(todo) = app.todos.get(index) else {
+        return false;
+    };
+
+    buf.bytes.clear();
+    buf.bytes.extend_from_slice(todo.note.to_str().as_bytes());
+    buf.bytes.push(0);
+    true
+}
+
+/// 文字列バッファの内容を取得します
+///
+/// # 引数
+///
+/// * `buf` - 文字列バッファへの参照
+///
+/// # 戻り値
+///
+/// バッファ内のNUL終端文字列への参照を返します。
+/// 参照はバッファに次の書き込みを行うか、バッファを解放するまで有効です。
+#[ffi_export]
+pub fn string_buf_ptr(buf: &StringBuf) -> char_p::Ref<'_> {
+    // バッファは常にNUL終端され、内部にNULを含まない
+    let c_str = std::ffi::CStr::from_bytes_with_nul(&buf.bytes).unwrap();
+    char_p::Ref::from(c_str)
+}
+
+/// 文字列バッファのメモリを解放します
+///
+/// # 引数
+///
+/// * `_buf` - 解放する文字列バッファ
+#[ffi_export]
+pub fn string_buf_free(_buf: repr_c::Box<StringBuf>) {
+    // repr_c::Box はドロップ時に自動的にメモリを解放します
+}
+
+/// Todoリストを指定した件数ごとに分割し、そのうちの1つを新しいアプリケーションとして取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `chunk_size` - 1つのチャンクに含めるTodoの件数
+/// * `chunk_index` - 取得するチャンクのインデックス（0から始まる）
+///
+/// # 戻り値
+///
+/// 指定したチャンクのTodoをコピーした新しいAppを返します。最後のチャンクは
+/// `chunk_size`件未満になる場合があります。`chunk_size`が0の場合や
+/// `chunk_index`が範囲外の場合は空のAppを返します。
+/// 返されたAppは`app_free`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, chunk_app};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in 1..=5 {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// let chunk = chunk_app(&app, 2, 2);
+/// assert_eq!(chunk.todos.len(), 1);
+/// assert_eq!(chunk.todos[0].id, 5);
+/// ```
+#[ffi_export]
+pub fn chunk_app(app: &App, chunk_size: usize, chunk_index: usize) -> repr_c::Box<App> {
+    let todos: Vec<Todo> = if chunk_size == 0 {
+        Vec::new()
+    } else {
+        app.todos
+            .chunks(chunk_size)
+            .nth(chunk_index)
+            .map(<[Todo]>::to_vec)
+            .unwrap_or_default()
+    };
+
+    Box::new(App {
+        todos: todos.into(),
+        ..Default::default()
+    })
+    .into()
+}
+
+/// ノートが最も長いTodoのIDを取得します
+///
+/// ノートの長さはUTF-8のバイト数で比較します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// ノートが最も長いTodoのIDを返します。同じ長さの場合は先に出現したTodoのIDを返し、
+/// Todoが存在しない場合は-1を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, id_of_longest_note};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// assert_eq!(id_of_longest_note(&app), -1);
+///
+/// for (id, note) in [(1, "短い"), (2, "とても長いノート")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+/// assert_eq!(id_of_longest_note(&app), 2);
+/// ```
+#[ffi_export]
+pub fn id_of_longest_note(app: &App) -> i32 {
+    let mut longest: Option<&Todo> = None;
+    for todo in app.todos.iter() {
+        // 同じ長さの場合は先に出現したTodoを優先する
+        if longest.is_none_or(|l| todo.note.to_str().len() > l.note.to_str().len()) {
+            longest = Some(todo);
+        }
+    }
+
+    longest.map(|todo| todo.id).unwrap_or(-1) // エラー値
+}
+
+/// タイムスタンプ付きのログ形式のTodoを追加します
+///
+/// ノートは`[<timestamp>] <message>`の形式で保存されます。
+/// `message`は最初のNULバイトで終わるため、組み立てたノートにNULバイトが含まれることはありません。
+///
+/// # 引数
+///
+/// * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+/// * `id` - 追加するTodoの一意識別子
+/// * `message` - ログメッセージ
+/// * `timestamp` - ノートの先頭に付与するタイムスタンプ（UNIX時間など）
+///
+/// # 戻り値
+///
+/// 追加が成功した場合は`true`を返します。同じIDのTodoが既に存在する場合や、
+/// Todoの数が`set_max_todos`で設定した上限に達している場合は追加せずに`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_log_entry};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let message = CString::new("起動しました").unwrap();
+/// assert!(add_log_entry(&mut app, 1, char_p::Ref::from(message.as_ref()), 1700000000));
+/// assert_eq!(app.todos[0].note.to_str(), "[1700000000] 起動しました");
+/// ```
+#[ffi_export]
+pub fn add_log_entry(app: &mut App, id: i32, message: char_p::Ref<'_>, timestamp: i64) -> bool {
+    let note = format!("[{}] {}", timestamp, message.to_str());
+    add_todo_str(app, id, &note) == AddResult::Ok
+}
+
+/// 重複を除いたノートの種類数を取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 異なるノートの数を返します。Todoが存在しない場合は0を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, distinct_note_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "牛乳"), (2, "卵"), (3, "牛乳")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(distinct_note_count(&app), 2);
+/// ```
+#[ffi_export]
+pub fn distinct_note_count(app: &App) -> usize {
+    app.todos
+        .iter()
+        .map(|todo| todo.note.to_str())
+        .collect::<HashSet<&str>>()
+        .len()
+}
+
+/// Todoのスナップショットを作成してから順番に走査します
+///
+/// 走査を始める前にIDとノートをローカルのバッファへコピーするため、
+/// `callback`の中で元のアプリケーションにTodoを追加・変更しても安全です。
+/// 走査されるのは呼び出し時点のTodoのみで、走査中に追加されたTodoは含まれません。
+///
+/// `callback`の中で`app`を`&mut App`として変更できるように、`app`は参照ではなく
+/// ポインタとして受け取り、スナップショットを作成した後は`app`を参照しません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへのポインタ（NULLの場合は何もしません）
+/// * `callback` - 各Todoに対して呼び出される関数（渡されるノートへの参照は呼び出し中のみ有効）
+/// * `user_data` - `callback`にそのまま渡される任意のポインタ
+///
+/// # Safety
+///
+/// `app`はNULLまたは有効な`App`を指している必要があります。呼び出し側は、この関数の
+/// 実行中に`app`への`&App`や`&mut App`を保持し続けてはいけません。`callback`の中で
+/// 変更する場合は、`app`と同じポインタから`&mut App`を作成してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, for_each_todo_snapshot};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::c_void;
+///
+/// extern "C" fn print_todo(id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) {
+///     println!("{}: {}", id, note.to_str());
+/// }
+///
+/// let app = App::default();
+/// unsafe { for_each_todo_snapshot(&app, print_todo, std::ptr::null_mut()) };
+/// ```
+#[ffi_export]
+pub unsafe fn for_each_todo_snapshot<'note>(
+    app: *const App,
+    callback: extern "C" fn(id: i32, note: char_p::Ref<'note>, user_data: *mut c_void),
+    user_data: *mut c_void,
+) {
+    // 参照はスナップショットの作成中のみ有効にし、コールバックの呼び出し前に手放す
+    let Some(app) = (unsafe { app.as_ref() }) else {
+        return;
+    };
+    let snapshot: Vec<(i32, char_p::Box)> = app
+        .todos
+        .iter()
+        .map(|todo| (todo.id, todo.note.clone()))
+        .collect();
+
+    for (id, note) in &snapshot {
+        // Safety: スナップショットはこの関数の終了まで解放されないため、
+        // コールバックの呼び出し中は参照が有効
+        callback(*id, unsafe { note_ref_for_callback(note) }, user_data);
+    }
+}
+
+/// 指定した範囲のTodoの並び順を反転します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `start` - 範囲の開始インデックス（この値を含む）
+/// * `end` - 範囲の終了インデックス（この値を含まない）
+///
+/// # 戻り値
+///
+/// 反転に成功した場合は`true`を返します。
+/// `start > end`または`end`がTodoの数を超える場合は何もせずに`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_todo_id_at, reverse_range};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in 1..=4 {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert!(reverse_range(&mut app, 1, 3));
+/// assert_eq!(get_todo_id_at(&app, 1), 3);
+/// assert_eq!(get_todo_id_at(&app, 2), 2);
+/// ```
+#[ffi_export]
+pub fn reverse_range(app: &mut App, start: usize, end: usize) -> bool {
+    if start > end || end > app.todos.len() {
+        return false;
+    }
+
+    app.todos[start..end].reverse();
+    true
+}
+
+/// 条件を満たすTodoが1つでも存在するかどうかを判定します
+///
+/// 条件を満たすTodoが見つかった時点で走査を終了し、以降のTodoに対して
+/// `predicate`は呼び出されません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `predicate` - 各Todoに対して呼び出される判定関数（渡されるノートへの参照は呼び出し中のみ有効）
+/// * `user_data` - `predicate`にそのまま渡される任意のポインタ
+///
+/// # 戻り値
+///
+/// 条件を満たすTodoが存在する場合は`true`、存在しない場合（空の場合を含む）は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, any_todo_matches};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::{c_void, CString};
+///
+/// extern "C" fn is_urgent(_id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) -> bool {
+///     note.to_str().contains("至急")
+/// }
+///
+/// let mut app = App::default();
+/// let note = CString::new("至急: 電話する").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert!(any_todo_matches(&app, is_urgent, std::ptr::null_mut()));
+/// ```
+#[ffi_export]
+pub fn any_todo_matches<'app>(
+    app: &'app App,
+    predicate: extern "C" fn(id: i32, note: char_p::Ref<'app>, user_data: *mut c_void) -> bool,
+    user_data: *mut c_void,
+) -> bool {
+    app.todos
+        .iter()
+        .any(|todo| predicate(todo.id, todo.note.as_ref(), user_data))
+}
+
+/// すべてのTodoが条件を満たすかどうかを判定します
+///
+/// 条件を満たさないTodoが見つかった時点で走査を終了し、以降のTodoに対して
+/// `predicate`は呼び出されません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `predicate` - 各Todoに対して呼び出される判定関数（渡されるノートへの参照は呼び出し中のみ有効）
+/// * `user_data` - `predicate`にそのまま渡される任意のポインタ
+///
+/// # 戻り値
+///
+/// すべてのTodoが条件を満たす場合は`true`を返します。Todoが存在しない場合も`true`を返します。
+#[ffi_export]
+pub fn all_todos_match<'app>(
+    app: &'app App,
+    predicate: extern "C" fn(id: i32, note: char_p::Ref<'app>, user_data: *mut c_void) -> bool,
+    user_data: *mut c_void,
+) -> bool {
+    app.todos
+        .iter()
+        .all(|todo| predicate(todo.id, todo.note.as_ref(), user_data))
+}
+
+/// すべてのTodoのIDをJSON配列として取得します
+///
+/// IDの集合だけを軽量に同期したい場合に利用します。ノートは含まれません。
+/// `serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// `[1,2,3]`の形式のJSON配列をリストの順番で返します。Todoが存在しない場合は`[]`を返します。
+/// 返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, ids_to_json};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(ids_to_json(&app).to_str(), "[1,2]");
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn ids_to_json(app: &App) -> char_p::Box {
+    let ids: Vec<i32> = app.todos.iter().map(|todo| todo.id).collect();
+    owned_string(serde_json::to_string(&ids).unwrap())
+}
+
+/// 条件を満たすTodoを取り除き、新しいアプリケーションとして取得します
+///
+/// アーカイブなどのために、条件に一致するTodoだけを元のアプリケーションから移動します。
+/// 移動したTodoと残ったTodoは、それぞれ元の順番を保ちます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `predicate` - 各Todoに対して呼び出される判定関数（渡されるノートへの参照は呼び出し中のみ有効）
+/// * `user_data` - `predicate`にそのまま渡される任意のポインタ
+///
+/// # 戻り値
+///
+/// 条件を満たしたTodoを持つ新しいAppを返します。返されたAppは`app_free`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, drain_matching};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::{c_void, CString};
+///
+/// extern "C" fn is_even(id: i32, _note: char_p::Ref<'_>, _user_data: *mut c_void) -> bool {
+///     id % 2 == 0
+/// }
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in 1..=4 {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// let drained = drain_matching(&mut app, is_even, std::ptr::null_mut());
+/// assert_eq!(drained.todos.len(), 2);
+/// assert_eq!(app.todos.len(), 2);
+/// ```
+#[ffi_export]
+pub fn drain_matching<'note>(
+    app: &mut App,
+    predicate: extern "C" fn(id: i32, note: char_p::Ref<'note>, user_data: *mut c_void) -> bool,
+    user_data: *mut c_void,
+) -> repr_c::Box<App> {
+    let todos: Vec<Todo> = std::mem::replace(&mut app.todos, Vec::new().into()).into();
+
+    let (drained, kept): (Vec<Todo>, Vec<Todo>) = todos.into_iter().partition(|todo| {
+        // Safety: ノートは判定関数の呼び出し中は解放されない
+        predicate(
+            todo.id,
+            unsafe { note_ref_for_callback(&todo.note) },
+            user_data,
+        )
+    });
+
+    app.todos = kept.into();
+    for todo in &drained {
+        notify_change(app, todo.id);
+    }
+    Box::new(App {
+        todos: drained.into(),
+        ..Default::default()
+    })
+    .into()
+}
+
+/// すべてのノートに共通する最長の接頭辞を取得します
+///
+/// 接頭辞は文字単位で比較するため、マルチバイト文字の途中で途切れることはありません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 共通の接頭辞を返します。Todoが1件の場合はそのノート全体を返し、
+/// Todoが存在しない場合や先頭の文字から異なる場合は空文字列を返します。
+/// 返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, common_note_prefix};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "買い物: 牛乳"), (2, "買い物: 卵")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(common_note_prefix(&app).to_str(), "買い物: ");
+/// ```
+#[ffi_export]
+pub fn common_note_prefix(app: &App) -> char_p::Box {
+    let mut notes = app.todos.iter().map(|todo| todo.note.to_str());
+    let Some(mut prefix) = notes.next() else {
+        return owned_string(String::new());
+    };
+
+    for note in notes {
+        // 文字単位で一致する部分の末尾のバイト位置を求める
+        let common_len = prefix
+            .char_indices()
+            .zip(note.chars())
+            .find(|((_, a), b)| a != b)
+            .map(|((i, _), _)| i)
+            .unwrap_or_else(|| prefix.len().min(note.len()));
+        prefix = &prefix[..common_len];
+    }
+
+    owned_string(prefix.to_string())
+}
+
+/// 指定インデックスのTodoの状態を次に進めます
+///
+/// 状態は`Todo`→`Doing`→`Done`→`Todo`の順に循環します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `index` - 対象のTodoのインデックス（0から始まる）
+///
+/// # 戻り値
+///
+/// 成功した場合は`true`、インデックスが範囲外の場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Status, Todo, cycle_status_at};
+///
+/// let mut app = App::default();
+/// app.todos = vec![Todo::new(1, "タスク")].into();
+///
+/// assert!(cycle_status_at(&mut app, 0));
+/// assert_eq!(app.todos[0].status, Status::Doing);
+/// ```
+#[ffi_export]
+pub fn cycle_status_at(app: &mut App, index: usize) -> bool {
+    let Some(todo) = app.todos.get_mut(index) else {
+        return false;
+    };
+
+    todo.set_status(todo.status.next());
+    true
+}
+
+/// 指定インデックスのTodoの状態を取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 取得するTodoのインデックス（0から始まる）
+/// * `out_status` - 取得した状態の書き込み先
+///
+/// # 戻り値
+///
+/// 成功した場合は`true`を返します。インデックスが範囲外の場合は`false`を返し、
+/// `out_status`は変更されません。
+#[ffi_export]
+pub fn get_status_at(app: &App, index: usize, out_status: &mut Status) -> bool {
+    let Some(todo) = app.todos.get(index) else {
+        return false;
+    };
+
+    *out_status = todo.status;
+    true
+}
+
+/// 指定インデックスのTodoの状態を設定します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `index` - 対象のTodoのインデックス（0から始まる）
+/// * `status` - 設定する状態
+///
+/// # 戻り値
+///
+/// 成功した場合は`true`、インデックスが範囲外の場合は`false`を返します。
+#[ffi_export]
+pub fn set_status_at(app: &mut App, index: usize, status: Status) -> bool {
+    let Some(todo) = app.todos.get_mut(index) else {
+        return false;
+    };
+
+    todo.set_status(status);
+    true
+}
+
+/// JSONに変換した際の1件あたりの最大の固定長部分
+///
+/// ノートを除いた、各フィールドが最も長くなる場合のTodo1件分の表現（区切りのカンマを含む）です。
+/// 分類は空の場合、優先度は既定値の場合に出力されないため、`CATEGORY_JSON_OVERHEAD`と
+/// `PRIORITY_JSON_OVERHEAD`として別に数えます。
+/// シリアライズするフィールドを追加した場合は、この値も更新してください。
+const TODO_JSON_OVERHEAD: usize =
+    r#"{"id":-2147483648,"note":"","status":"doing","done":false},"#.len();
+
+/// 分類が空でない場合に追加される固定長部分
+const CATEGORY_JSON_OVERHEAD: usize = r#","category":"""#.len();
+
+/// 優先度が既定値でない場合に追加される部分の最大の長さ
+const PRIORITY_JSON_OVERHEAD: usize = r#","priority":"high""#.len();
+
+/// JSON文字列としてエスケープした場合の最大のバイト数を計算します
+fn max_escaped_json_len(s: &str) -> usize {
+    s.bytes()
+        .map(|b| match b {
+            b'"' | b'\\' => 2,
+            // 制御文字は最大で`\u00XX`の6バイトになる
+            0x00..=0x1f => 6,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// アプリケーションをJSONに変換した際のバイト数の見積もりを取得します
+///
+/// 実際にJSON文字列を組み立てずに、バッファを事前に確保するための上限値を計算します。
+/// ノートと分類はエスケープが必要な文字（`"`、`\`、制御文字）ごとに最大の長さで見積もります。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// JSONのバイト数の見積もりを返します。この値は実際の長さ以上であることが保証され、
+/// 実際の長さを下回ることはありません（多めに見積もられます）。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, estimated_json_size};
+///
+/// let app = App::default();
+/// assert!(estimated_json_size(&app) >= "[]".len());
+/// ```
+#[ffi_export]
+pub fn estimated_json_size(app: &App) -> usize {
+    let variable: usize = app
+        .todos
+        .iter()
+        .map(|todo| {
+            let category = todo.category.to_str();
+            let category_len = if category.is_empty() {
+                0
+            } else {
+                CATEGORY_JSON_OVERHEAD + max_escaped_json_len(category)
+            };
+            let priority_len = if todo.priority == Priority::default() {
+                0
+            } else {
+                PRIORITY_JSON_OVERHEAD
+            };
+            max_escaped_json_len(todo.note.to_str()) + category_len + priority_len
+        })
+        .sum();
+
+    "[]".len() + app.todos.len() * TODO_JSON_OVERHEAD + variable
+}
+
+/// すべてのTodoのIDをリスト内の位置に合わせて振り直します
+///
+/// 各Todoには`base + インデックス`のIDが割り当てられます。`i32::MAX`を超える場合は
+/// `i32::MAX`で頭打ちになります。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `base` - 先頭のTodoに割り当てるID
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_todo_id_at, set_ids_to_positions};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 42, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut app, 7, char_p::Ref::from(note.as_ref()));
+///
+/// set_ids_to_positions(&mut app, 1);
+/// assert_eq!(get_todo_id_at(&app, 0), 1);
+/// assert_eq!(get_todo_id_at(&app, 1), 2);
+/// ```
+#[ffi_export]
+pub fn set_ids_to_positions(app: &mut App, base: i32) {
+    for (index, todo) in app.todos.iter_mut().enumerate() {
+        let id = (base as i64).saturating_add(index as i64);
+        todo.id = id.min(i32::MAX as i64) as i32;
+    }
+}
+
+/// 2つの文字列のレーベンシュタイン距離（編集距離）を文字単位で計算します
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // 直前の行だけを保持して計算する
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// 検索文字列に最も近いノートを持つTodoのインデックスを取得します
+///
+/// ノートと検索文字列のレーベンシュタイン距離（文字単位の編集距離）を比較し、
+/// 多少の入力ミスがあっても目的のTodoを見つけられるようにします。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `query` - 検索文字列
+/// * `max_distance` - 許容する最大の編集距離
+///
+/// # 戻り値
+///
+/// 編集距離が`max_distance`以下で最も小さいTodoのインデックスを返します。
+/// 同じ距離の場合は先に出現したTodoを優先し、該当するTodoがない場合は-1を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, find_closest_note};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("buy milk").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let query = CString::new("buy mlk").unwrap();
+/// assert_eq!(find_closest_note(&app, char_p::Ref::from(query.as_ref()), 1), 0);
+/// ```
+#[ffi_export]
+pub fn find_closest_note(app: &App, query: char_p::Ref<'_>, max_distance: usize) -> isize {
+    let query = query.to_str();
+    let mut best: Option<(usize, usize)> = None;
+
+    for (index, todo) in app.todos.iter().enumerate() {
+        let distance = levenshtein(todo.note.to_str(), query);
+        // 同じ距離の場合は先に出現したTodoを優先する
+        if distance <= max_distance && best.is_none_or(|(_, d)| distance < d) {
+            best = Some((index, distance));
+        }
+    }
+
+    best.map(|(index, _)| index as isize).unwrap_or(-1) // エラー値
+}
+
+/// 指定インデックスのTodoをJSONオブジェクトとして取得します
+///
+/// 1件単位で同期したい場合に利用します。`serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 取得するTodoのインデックス（0から始まる）
+///
+/// # 戻り値
+///
+/// `{"id":1,"note":"...","status":"todo","done":false}`の形式のJSONを返します。
+/// インデックスが範囲外の場合は空文字列を返します。
+/// 返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, todo_to_json_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(
+///     todo_to_json_at(&app, 0).to_str(),
+///     r#"{"id":1,"note":"牛乳を買う","status":"todo","done":false}"#
+/// );
+/// assert_eq!(todo_to_json_at(&app, 1).to_str(), "");
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn todo_to_json_at(app: &App, index: usize) -> char_p::Box {
+    let json = match app.todos.get(index) {
+        Some(todo) => serde_json::to_string(&TodoRecord::from_todo(todo)).unwrap(),
+        // エラーの場合は空文字列
+        None => String::new(),
+    };
+    owned_string(json)
+}
+
+/// IDの昇順を保つ位置にTodoを挿入します
+///
+/// Todoリストが既にIDの昇順に並んでいることを前提に、並び順を崩さない位置へ挿入します。
+/// `add_todo`と同様に、同じIDのTodoが既に存在する場合は挿入しません。
+///
+/// # 引数
+///
+/// * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+/// * `id` - 追加するTodoの一意識別子
+/// * `note` - Todoの内容を表す文字列
+///
+/// # 戻り値
+///
+/// 挿入した位置のインデックスを返します。挿入できなかった場合（IDが重複している場合や、
+/// Todoの数が`set_max_todos`で設定した上限に達している場合を含む）は-1を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_todo_id_at, insert_sorted_by_id};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut app, 3, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(insert_sorted_by_id(&mut app, 2, char_p::Ref::from(note.as_ref())), 1);
+/// assert_eq!(get_todo_id_at(&app, 1), 2);
+///
+/// // 既に存在するIDは挿入できない
+/// assert_eq!(insert_sorted_by_id(&mut app, 3, char_p::Ref::from(note.as_ref())), -1);
+/// ```
+#[ffi_export]
+pub fn insert_sorted_by_id(app: &mut App, id: i32, note: char_p::Ref<'_>) -> isize {
+    if index_of(app, id).is_some() || remaining_slots(app) == 0 {
+        return -1;
+    }
+    let Ok(todo) = Todo::try_new(id, note.to_str()) else {
+        return -1;
+    };
+    let index = app.todos.partition_point(|t| t.id < id);
+
+    let mut todos: Vec<Todo> = std::mem::replace(&mut app.todos, Vec::new().into()).into();
+    todos.insert(index, todo);
+    app.todos = todos.into();
+    notify_change(app, id);
+
+    index as isize
+}
+
+/// 差分比較に適した正規化済みのJSONを取得します
+///
+/// Todoをソートし、オブジェクトのキーを固定の順番（`id`、`note`、`status`、`done`、`category`、
+/// `priority`。分類と優先度は既定値の場合は省略）で出力するため、
+/// 同じ内容のTodoを異なる順番で保持している2つのアプリケーションからは、
+/// バイト単位で同一のJSONが得られます。`serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// IDの昇順に並べたTodoのJSON配列を返します。IDが同じ場合はノート、状態、完了フラグ、
+/// 分類、優先度の順に比較します。
+/// 返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, app_to_canonical_json};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(
+///     app_to_canonical_json(&app).to_str(),
+///     concat!(
+///         r#"[{"id":1,"note":"タスク","status":"todo","done":false},"#,
+///         r#"{"id":2,"note":"タスク","status":"todo","done":false}]"#
+///     )
+/// );
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn app_to_canonical_json(app: &App) -> char_p::Box {
+    let mut records = app.to_records();
+    records.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+    owned_string(serde_json::to_string(&records).unwrap())
+}
+
+/// ノートが空、または空白文字のみのTodoの数を取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// ノートが空文字列、またはスペース・タブ・改行などの空白文字だけで構成されている
+/// Todoの数を返します。Todoが存在しない場合は0を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, blank_note_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "牛乳を買う"), (2, ""), (3, "  \t")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(blank_note_count(&app), 2);
+/// ```
+#[ffi_export]
+pub fn blank_note_count(app: &App) -> usize {
+    app.todos
+        .iter()
+        .filter(|todo| todo.note.to_str().trim().is_empty())
+        .count()
+}
+
+/// 2回以上登場するノートを持つTodoのIDを取得します
+///
+/// `distinct_note_count`を補完する関数です。重複したノートを持つTodoは、
+/// 最初の1件も含めてすべて結果に含まれます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// ノートがリスト内に2回以上登場するTodoのIDをリストの順番で返します。
+/// 重複がない場合は空のベクタを返します。
+/// 返されたベクタは`free_i32_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, ids_with_repeated_notes};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "牛乳"), (2, "卵"), (3, "牛乳")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(&ids_with_repeated_notes(&app)[..], &[1, 3]);
+/// ```
+#[ffi_export]
+pub fn ids_with_repeated_notes(app: &App) -> repr_c::Vec<i32> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for todo in app.todos.iter() {
+        *counts.entry(todo.note.to_str()).or_default() += 1;
+    }
+
+    app.todos
+        .iter()
+        .filter(|todo| counts[todo.note.to_str()] >= 2)
+        .map(|todo| todo.id)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// 2つのTodoのノートを入れ替えます
+///
+/// 入れ替わるのはノートのみで、IDや状態は元の位置に残ります。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `a` - 入れ替えるTodoのインデックス
+/// * `b` - 入れ替えるもう一方のTodoのインデックス
+///
+/// # 戻り値
+///
+/// 入れ替えに成功した場合は`true`、いずれかのインデックスが範囲外の場合は`false`を返します。
+/// `a`と`b`が同じ場合は何も変更せずに`true`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, swap_notes};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "牛乳"), (2, "卵")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert!(swap_notes(&mut app, 0, 1));
+/// assert_eq!(app.todos[0].id, 1);
+/// assert_eq!(app.todos[0].note.to_str(), "卵");
+/// ```
+#[ffi_export]
+pub fn swap_notes(app: &mut App, a: usize, b: usize) -> bool {
+    if a >= app.todos.len() || b >= app.todos.len() {
+        return false;
+    }
+    if a == b {
+        return true;
+    }
+
+    let (low, high) = (a.min(b), a.max(b));
+    let (head, tail) = app.todos.split_at_mut(high);
+    std::mem::swap(&mut head[low].note, &mut tail[0].note);
+    true
+}
+
+/// C側から直接インデックスアクセスできる固定長のTodo表現
+///
+/// `note_ptr`はアプリケーション内部のノートのバイト列を指し、NUL終端は含みません。
+/// `export_todo_c_array`で取得した配列の要素としてのみ利用します。
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TodoC {
+    /// TodoのID
+    pub id: i32,
+    /// ノートのUTF-8バイト列の先頭
+    pub note_ptr: *const u8,
+    /// ノートのバイト数（NUL終端を含まない）
+    pub note_len: usize,
+}
+
+/// `export_todo_c_array`で作成した配列を保持するハンドル
+#[derive_ReprC]
+#[repr(opaque)]
+pub struct TodoCArrayHandle {
+    entries: Vec<TodoC>,
+}
+
+/// Todoリストを固定長の構造体配列として取得します
+///
+/// 文字列の変換やコピーを行わずにC側から直接インデックスアクセスしたい場合に利用します。
+/// 配列の各要素はアプリケーション内部のノートを参照するため、
+/// 以下の間のみ有効です。
+///
+/// - 返されたハンドルを`free_todo_c_array`で解放するまで
+/// - かつ、`app`を変更または解放するまで
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `out_ptr` - 配列の先頭へのポインタの書き込み先
+/// * `out_len` - 配列の要素数の書き込み先
+///
+/// # 戻り値
+///
+/// 配列を保持するハンドルを返します。使い終わったら`free_todo_c_array`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, export_todo_c_array, free_todo_c_array};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let mut ptr = std::ptr::null();
+/// let mut len = 0;
+/// let handle = export_todo_c_array(&app, &mut ptr, &mut len);
+///
+/// let todos = unsafe { std::slice::from_raw_parts(ptr, len) };
+/// assert_eq!(todos[0].id, 1);
+/// assert_eq!(todos[0].note_len, "牛乳を買う".len());
+///
+/// free_todo_c_array(handle);
+/// ```
+#[ffi_export]
+pub fn export_todo_c_array(
+    app: &App,
+    out_ptr: &mut *const TodoC,
+    out_len: &mut usize,
+) -> repr_c::Box<TodoCArrayHandle> {
+    let entries: Vec<TodoC> = app
+        .todos
+        .iter()
+        .map(|todo| {
+            let bytes = todo.note.to_str().as_bytes();
+            TodoC {
+                id: todo.id,
+                note_ptr: bytes.as_ptr(),
+                note_len: bytes.len(),
+            }
+        })
+        .collect();
+
+    let handle = Box::new(TodoCArrayHandle { entries });
+    *out_ptr = handle.entries.as_ptr();
+    *out_len = handle.entries.len();
+    handle.into()
+}
+
+/// `export_todo_c_array`で作成した配列を解放します
+///
+/// 解放後は、配列およびその要素の`note_ptr`を参照しないでください。
+///
+/// # 引数
+///
+/// * `_handle` - 解放するハンドル
+#[ffi_export]
+pub fn free_todo_c_array(_handle: repr_c::Box<TodoCArrayHandle>) {
+    // repr_c::Box はドロップ時に自動的にメモリを解放します
+}
+
+/// 複数のキーワードのいずれかを含むTodoを検索します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `needles` - 検索するキーワードの配列
+///
+/// # 戻り値
+///
+/// ノートにいずれかのキーワードを含むTodoのインデックスを昇順で返します。
+/// 複数のキーワードに一致したTodoも1回だけ含まれます。
+/// `needles`が空の場合は何にも一致せず、空のベクタを返します。
+/// 返されたベクタは`free_usize_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, search_any};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "牛乳を買う"), (2, "レポートを書く"), (3, "卵を買う")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// let milk = CString::new("牛乳").unwrap();
+/// let egg = CString::new("卵").unwrap();
+/// let needles = [char_p::Ref::from(milk.as_ref()), char_p::Ref::from(egg.as_ref())];
+///
+/// assert_eq!(&search_any(&app, needles[..].into())[..], &[0, 2]);
+/// ```
+#[ffi_export]
+pub fn search_any(app: &App, needles: c_slice::Ref<'_, char_p::Ref<'_>>) -> repr_c::Vec<usize> {
+    app.todos
+        .iter()
+        .enumerate()
+        .filter(|(_, todo)| {
+            let note = todo.note.to_str();
+            needles.iter().any(|needle| note.contains(needle.to_str()))
+        })
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// 長さ付きのバイト列から指定インデックスのTodoのノートを設定します
+///
+/// NUL終端文字列を扱わない言語から、文字列をコピーせずに渡す場合に利用します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `index` - 更新するTodoのインデックス（0から始まる）
+/// * `bytes` - 新しいノートのUTF-8バイト列（NUL終端は不要）
+///
+/// # 戻り値
+///
+/// 設定に成功した場合は`true`を返します。
+/// インデックスが範囲外、バイト列が不正なUTF-8、またはNULを含む場合は`false`を返し、
+/// Todoは変更されません。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, set_note_from_bytes_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert!(set_note_from_bytes_at(&mut app, 0, "卵を買う".as_bytes().into()));
+/// assert_eq!(app.todos[0].note.to_str(), "卵を買う");
+/// ```
+#[ffi_export]
+pub fn set_note_from_bytes_at(app: &mut App, index: usize, bytes: c_slice::Ref<'_, u8>) -> bool {
+    let Some(todo) = app.todos.get_mut(index) else {
+        return false;
+    };
+    let Ok(note) = std::str::from_utf8(bytes.as_slice()) else {
+        return false;
+    };
+    let Ok(c_string) = std::ffi::CString::new(note) else {
+        return false;
+    };
+
+    todo.note = char_p::Box::from(c_string);
+    true
+}
+
+/// ノートのバイト長のヒストグラムを取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `bucket_size` - 1つの区間の幅（バイト数）
+///
+/// # 戻り値
+///
+/// `i`番目の要素に、バイト長が`[i * bucket_size, (i + 1) * bucket_size)`に含まれる
+/// ノートの数を格納したベクタを返します。ベクタの長さは最も長いノートを含む区間までです。
+/// `bucket_size`が0の場合やTodoが存在しない場合は空のベクタを返します。
+/// 返されたベクタは`free_usize_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, note_length_histogram};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "ab"), (2, "abcdef"), (3, "abc")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(&note_length_histogram(&app, 4)[..], &[2, 1]);
+/// ```
+#[ffi_export]
+pub fn note_length_histogram(app: &App, bucket_size: usize) -> repr_c::Vec<usize> {
+    if bucket_size == 0 {
+        return Vec::new().into();
+    }
+
+    let mut histogram: Vec<usize> = Vec::new();
+    for todo in app.todos.iter() {
+        let bucket = todo.note.to_str().len() / bucket_size;
+        if bucket >= histogram.len() {
+            histogram.resize(bucket + 1, 0);
+        }
+        histogram[bucket] += 1;
+    }
+
+    histogram.into()
+}
+
+/// 指定インデックスのTodoの内容をJSONで置き換えます
+///
+/// `{"id":1,"note":"...","status":"todo","done":false}`形式のオブジェクトを解析し、
+/// Todoの全フィールドをまとめて置き換えます。`status`を省略した場合は`todo`、
+/// `done`を省略した場合は`false`になります。`done`が`true`の場合、状態は`done`になります。
+/// `serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `index` - 置き換えるTodoのインデックス（0から始まる）
+/// * `json` - 新しいTodoの内容を表すJSONオブジェクト
+///
+/// # 戻り値
+///
+/// 置き換えに成功した場合は`true`を返します。
+/// インデックスが範囲外、またはJSONを解析できない場合は`false`を返し、Todoは変更されません。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Status, add_todo, update_todo_from_json_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let json = CString::new(r#"{"id":10,"note":"卵を買う","status":"done"}"#).unwrap();
+/// assert!(update_todo_from_json_at(&mut app, 0, char_p::Ref::from(json.as_ref())));
+/// assert_eq!(app.todos[0].id, 10);
+/// assert_eq!(app.todos[0].note.to_str(), "卵を買う");
+/// assert_eq!(app.todos[0].status, Status::Done);
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn update_todo_from_json_at(app: &mut App, index: usize, json: char_p::Ref<'_>) -> bool {
+    let Some(todo) = app.todos.get_mut(index) else {
+        return false;
+    };
+    let Ok(record) = serde_json::from_str::<TodoRecord>(json.to_str()) else {
+        return false;
+    };
+    let Some(new_todo) = record.into_todo() else {
+        return false;
+    };
+
+    *todo = new_todo;
+    true
+}
+
+/// 期待するIDのうち、アプリケーションに存在しないものを取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `expected` - 存在するはずのIDの配列
+///
+/// # 戻り値
+///
+/// `expected`に含まれ、どのTodoのIDとも一致しないIDを`expected`の順番で返します。
+/// `expected`に同じIDが複数回含まれる場合も1回だけ返します。
+/// 返されたベクタは`free_i32_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, missing_ids};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in [1, 2] {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(&missing_ids(&app, [1, 2, 3][..].into())[..], &[3]);
+/// ```
+#[ffi_export]
+pub fn missing_ids(app: &App, expected: c_slice::Ref<'_, i32>) -> repr_c::Vec<i32> {
+    let present: HashSet<i32> = app.todos.iter().map(|todo| todo.id).collect();
+    let mut reported = HashSet::new();
+
+    expected
+        .iter()
+        .copied()
+        .filter(|id| !present.contains(id) && reported.insert(*id))
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// アプリケーションに存在するIDのうち、期待するIDに含まれないものを取得します
+///
+/// `missing_ids`と組み合わせることで、期待するIDの集合との差分をすべて求められます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `expected` - 存在するはずのIDの配列
+///
+/// # 戻り値
+///
+/// `expected`に含まれないTodoのIDをリストの順番で返します。
+/// 同じIDのTodoが複数ある場合も1回だけ返します。
+/// 返されたベクタは`free_i32_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, extra_ids};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in [1, 2, 3] {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(&extra_ids(&app, [1, 2][..].into())[..], &[3]);
+/// ```
+#[ffi_export]
+pub fn extra_ids(app: &App, expected: c_slice::Ref<'_, i32>) -> repr_c::Vec<i32> {
+    let expected: HashSet<i32> = expected.iter().copied().collect();
+    let mut reported = HashSet::new();
+
+    app.todos
+        .iter()
+        .map(|todo| todo.id)
+        .filter(|id| !expected.contains(id) && reported.insert(*id))
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// 別のアプリケーションのTodoを移動して末尾に追加します
+///
+/// ノートの文字列は複製せずにそのまま移動するため、大量のTodoを統合する場合でも
+/// 追加のメモリ確保はリストの拡張分のみです。
+///
+/// # 引数
+///
+/// * `dst` - 追加先のアプリケーションインスタンスへの可変参照
+/// * `src` - 追加元のアプリケーションインスタンス（この関数内で解放されます）
+///
+/// # 戻り値
+///
+/// 実際に追加したTodoの数を返します。`dst`に`set_max_todos`で上限を設定している場合は、
+/// 上限に達した時点で残りを追加せず、追加されなかったTodoは`src`とともに解放されます。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{add_todo, app_new, append_and_consume, get_todo_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut dst = app_new();
+/// let mut src = app_new();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut dst, 1, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut src, 2, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(append_and_consume(&mut dst, src), 1);
+/// assert_eq!(get_todo_count(&dst), 2);
+/// ```
+#[ffi_export]
+pub fn append_and_consume(dst: &mut App, src: repr_c::Box<App>) -> usize {
+    let src: Box<App> = src.into();
+    let mut appended: Vec<Todo> = src.todos.into();
+    appended.truncate(remaining_slots(dst));
+    let ids: Vec<i32> = appended.iter().map(|todo| todo.id).collect();
+
+    let mut todos: Vec<Todo> = std::mem::replace(&mut dst.todos, Vec::new().into()).into();
+    todos.extend(appended);
+    dst.todos = todos.into();
+    for &id in &ids {
+        notify_change(dst, id);
+    }
+    ids.len()
+}
+
+/// ノートのバイト長の中央値を取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// ノートのUTF-8バイト長の中央値を返します。Todoが偶数個の場合は中央の2つの平均を返します。
+/// Todoが存在しない場合は`0.0`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, median_note_byte_len};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "a"), (2, "abcdef"), (3, "abc")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(median_note_byte_len(&app), 3.0);
+/// ```
+#[ffi_export]
+pub fn median_note_byte_len(app: &App) -> f64 {
+    let mut lengths: Vec<usize> = app
+        .todos
+        .iter()
+        .map(|todo| todo.note.to_str().len())
+        .collect();
+    if lengths.is_empty() {
+        return 0.0;
+    }
+
+    lengths.sort_unstable();
+    let middle = lengths.len() / 2;
+    if lengths.len() % 2 == 0 {
+        (lengths[middle - 1] + lengths[middle]) as f64 / 2.0
+    } else {
+        lengths[middle] as f64
+    }
+}
+
+/// 指定インデックスのTodoの分類を設定します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `index` - 対象のTodoのインデックス（0から始まる）
+/// * `category` - 設定する分類（空文字列で未分類に戻ります）
+///
+/// # 戻り値
+///
+/// 成功した場合は`true`、インデックスが範囲外の場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_category_at, set_category_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let category = CString::new("買い物").unwrap();
+/// assert!(set_category_at(&mut app, 0, char_p::Ref::from(category.as_ref())));
+/// assert_eq!(get_category_at(&app, 0).to_str(), "買い物");
+/// ```
+#[ffi_export]
+pub fn set_category_at(app: &mut App, index: usize, category: char_p::Ref<'_>) -> bool {
+    let Some(todo) = app.todos.get_mut(index) else {
+        return false;
+    };
+
+    todo.category = category.to_owned();
+    true
+}
+
+/// 指定インデックスのTodoの分類を取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 取得するTodoのインデックス（0から始まる）
+///
+/// # 戻り値
+///
+/// 分類のコピーを返します。未分類の場合やインデックスが範囲外の場合は空文字列を返します。
+/// 返された文字列は`free_string`で解放してください。
+#[ffi_export]
+pub fn get_category_at(app: &App, index: usize) -> char_p::Box {
+    match app.todos.get(index) {
+        Some(todo) => track_string(todo.category.clone()),
+        None => owned_string(String::new()),
+    }
+}
+
+/// 指定した分類に属するTodoのIDを取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `category` - 検索する分類（完全一致）
+///
+/// # 戻り値
+///
+/// 分類が一致するTodoのIDをリストの順番で返します。
+/// 空文字列を指定すると未分類のTodoのIDを返します。
+/// 返されたベクタは`free_i32_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, ids_in_category, set_category_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in [1, 2, 3] {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// let work = CString::new("仕事").unwrap();
+/// set_category_at(&mut app, 0, char_p::Ref::from(work.as_ref()));
+/// set_category_at(&mut app, 2, char_p::Ref::from(work.as_ref()));
+///
+/// assert_eq!(&ids_in_category(&app, char_p::Ref::from(work.as_ref()))[..], &[1, 3]);
+/// ```
+#[ffi_export]
+pub fn ids_in_category(app: &App, category: char_p::Ref<'_>) -> repr_c::Vec<i32> {
+    let category = category.to_str();
+
+    app.todos
+        .iter()
+        .filter(|todo| todo.category.to_str() == category)
+        .map(|todo| todo.id)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// 分類ごとのTodoの件数をJSONで取得します
+///
+/// `serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 分類をキー、件数を値とするJSONオブジェクトを返します。キーは辞書順に並びます。
+/// 未分類のTodoは空文字列`""`のキーで数えられ、Todoが存在しない場合は`{}`を返します。
+/// 返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, category_counts, set_category_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in [1, 2, 3] {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// let work = CString::new("work").unwrap();
+/// set_category_at(&mut app, 0, char_p::Ref::from(work.as_ref()));
+/// set_category_at(&mut app, 1, char_p::Ref::from(work.as_ref()));
+///
+/// assert_eq!(category_counts(&app).to_str(), r#"{"":1,"work":2}"#);
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn category_counts(app: &App) -> char_p::Box {
+    let mut counts: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+    for todo in app.todos.iter() {
+        *counts.entry(todo.category.to_str()).or_default() += 1;
+    }
+
+    owned_string(serde_json::to_string(&counts).unwrap())
+}
+
+/// 指定インデックスのTodoのノートを、UTF-8の文字境界で切り詰めてバッファにコピーします
+///
+/// バッファが足りない場合でも、文字の途中で切らずに収まるだけの文字をコピーし、
+/// 常にNUL終端された有効なUTF-8文字列を書き込みます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 取得するTodoのインデックス（0から始まる）
+/// * `buf` - 書き込み先のバッファ（NUL終端の1バイトを含めた長さ）
+///
+/// # 戻り値
+///
+/// 書き込んだバイト数（NUL終端を含まない）を返します。
+/// インデックスが範囲外の場合は`-1`を返し、バッファは変更されません。
+/// バッファの長さが0の場合は何も書き込まずに`0`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, copy_todo_note_truncated_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// // 「牛乳」の6バイトとNUL終端が収まり、「を」は途中で切らずに省かれる
+/// let mut buf = [0u8; 8];
+/// assert_eq!(copy_todo_note_truncated_at(&app, 0, (&mut buf[..]).into()), 6);
+/// assert_eq!(&buf[..7], "牛乳\0".as_bytes());
+/// ```
+#[ffi_export]
+pub fn copy_todo_note_truncated_at(app: &App, index: usize, buf: c_slice::Mut<'_, u8>) -> isize {
+    let Some(todo) = app.todos.get(index) else {
+        return -1;
+    };
+    let buf = buf.as_slice();
+    if buf.is_empty() {
+        return 0;
+    }
+
+    let note = todo.note.to_str();
+    let mut len = note.len().min(buf.len() - 1);
+    while !note.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    buf[..len].copy_from_slice(&note.as_bytes()[..len]);
+    buf[len] = 0;
+    len as isize
+}
+
+/// 符号なし整数をvarint（7ビットずつ、下位から順に）で書き込みます
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// varintを読み込み、`pos`を読み込んだ分だけ進めます
+///
+/// データが途中で終わっている場合や、`u64`に収まらない場合は`None`を返します。
+fn read_varint(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// IDをzigzag符号化したvarintで書き込みます
+fn write_id(out: &mut Vec<u8>, id: i32) {
+    // zigzag符号化で絶対値の小さい負数も短く表す
+    let zigzag = ((id << 1) ^ (id >> 31)) as u32;
+    write_varint(out, u64::from(zigzag));
+}
+
+/// `write_id`で書き込んだIDを読み込みます
+fn read_id(data: &[u8], pos: &mut usize) -> Option<i32> {
+    let zigzag = u32::try_from(read_varint(data, pos)?).ok()?;
+    Some((zigzag >> 1) as i32 ^ -((zigzag & 1) as i32))
+}
+
+/// 文字列をバイト数のvarintとUTF-8バイト列の順に書き込みます
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_varint(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// `write_str`で書き込んだ文字列を読み込みます
+///
+/// データが途中で終わっている場合や、不正なUTF-8の場合は`None`を返します。
+fn read_str<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a str> {
+    let len = usize::try_from(read_varint(data, pos)?).ok()?;
+    let end = pos.checked_add(len)?;
+    let s = std::str::from_utf8(data.get(*pos..end)?).ok()?;
+    *pos = end;
+    Some(s)
+}
+
+/// 1バイトを読み込みます
+fn read_u8(data: &[u8], pos: &mut usize) -> Option<u8> {
+    let byte = *data.get(*pos)?;
+    *pos += 1;
+    Some(byte)
+}
+
+/// TodoのIDとノートのみをvarintを使ったバイナリ形式で`out`に書き込みます
+#[cfg(feature = "varint")]
+fn encode_varint_stream(app: &App, out: &mut Vec<u8>) {
+    for todo in app.todos.iter() {
+        write_id(out, todo.id);
+        write_str(out, todo.note.to_str());
+    }
+}
+
+/// `encode_varint_stream`の形式からアプリケーションを組み立てます
+///
+/// データが不正な場合は`None`を返します。
+#[cfg(feature = "varint")]
+fn decode_varint_stream(data: &[u8]) -> Option<App> {
+    let mut todos = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let id = read_id(data, &mut pos)?;
+        let note = read_str(data, &mut pos)?;
+        todos.push(Todo::try_new(id, note).ok()?);
+    }
+    Some(App {
+        todos: todos.into(),
+        ..Default::default()
+    })
+}
+
+/// Todoのすべてのフィールドをバイナリ形式で`out`に書き込みます
+///
+/// ID、ノート、状態（1バイト）、完了フラグ（1バイト）、分類、優先度（1バイト）の順に書き込みます。
+/// Todoにフィールドを追加した場合は、この形式と`APP_SCHEMA_VERSION`も更新してください。
+fn encode_todo_stream(app: &App, out: &mut Vec<u8>) {
+    for todo in app.todos.iter() {
+        write_id(out, todo.id);
+        write_str(out, todo.note.to_str());
+        out.push(todo.status as u8);
+        out.push(u8::from(todo.done));
+        write_str(out, todo.category.to_str());
+        out.push(todo.priority as u8);
+    }
+}
+
+/// `encode_todo_stream`の形式からアプリケーションを組み立てます
+///
+/// データが途中で終わっている場合、列挙型の値が範囲外の場合、完了フラグと状態が
+/// 一致しない場合、文字列が不正なUTF-8またはNULを含む場合は`None`を返します。
+fn decode_todo_stream(data: &[u8]) -> Option<App> {
+    let mut todos = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let id = read_id(data, &mut pos)?;
+        let note = read_str(data, &mut pos)?;
+        let status = Status::from_u8(read_u8(data, &mut pos)?)?;
+        let done = match read_u8(data, &mut pos)? {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+        let category = read_str(data, &mut pos)?;
+        let priority = Priority::from_u8(read_u8(data, &mut pos)?)?;
+        if done != (status == Status::Done) {
+            return None;
+        }
+
+        let mut todo = Todo::try_new(id, note).ok()?;
+        todo.set_status(status);
+        todo.category = char_p::Box::from(std::ffi::CString::new(category).ok()?);
+        todo.priority = priority;
+        todos.push(todo);
+    }
+    Some(App {
+        todos: todos.into(),
+        ..Default::default()
+    })
+}
+
+/// アプリケーションをvarintを使ったバイナリ形式に変換します
+///
+/// 各Todoを、zigzag符号化したIDのvarint、ノートのバイト数のvarint、ノートのUTF-8バイト列の順に
+/// 連結します。protobufのような形式で、JSONよりも小さくなります。
+/// 状態、分類、優先度は含まれません。`varint`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 変換したバイト列を返します。返されたベクタは`free_u8_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, app_from_varint_stream, app_to_varint_stream};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, -1, char_p::Ref::from(note.as_ref()));
+///
+/// let bytes = app_to_varint_stream(&app);
+/// let restored = app_from_varint_stream(bytes[..].into());
+/// assert_eq!(restored.todos[0].id, -1);
+/// assert_eq!(restored.todos[0].note.to_str(), "牛乳を買う");
+/// ```
+#[cfg(feature = "varint")]
+#[ffi_export]
+pub fn app_to_varint_stream(app: &App) -> repr_c::Vec<u8> {
+    let mut out = Vec::new();
+    encode_varint_stream(app, &mut out);
+    out.into()
+}
+
+/// `app_to_varint_stream`で変換したバイト列からアプリケーションを復元します
+///
+/// `varint`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `data` - `app_to_varint_stream`で変換したバイト列
+///
+/// # 戻り値
+///
+/// 復元したアプリケーションを返します。データが途中で終わっている場合、IDが範囲外の場合、
+/// ノートが不正なUTF-8またはNULを含む場合は空のアプリケーションを返します。
+/// 返されたアプリケーションは`app_free`で解放してください。
+#[cfg(feature = "varint")]
+#[ffi_export]
+pub fn app_from_varint_stream(data: c_slice::Ref<'_, u8>) -> repr_c::Box<App> {
+    Box::new(decode_varint_stream(data.as_slice()).unwrap_or_default()).into()
+}
+
+/// 指定したIDのTodoを削除します
+///
+/// 同じIDのTodoが複数ある場合は、最初の1件のみを削除します。
+/// 残りのTodoの順番は変わりません。
+/// 削除した場合は、`set_on_change`で設定したコールバックを削除したIDで呼び出します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `id` - 削除するTodoのID
+///
+/// # 戻り値
+///
+/// 削除した場合は`true`、該当するTodoが存在しない場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_todo_count, remove_todo};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert!(remove_todo(&mut app, 1));
+/// assert_eq!(get_todo_count(&app), 0);
+/// assert!(!remove_todo(&mut app, 1));
+/// ```
+#[ffi_export]
+pub fn remove_todo(app: &mut App, id: i32) -> bool {
+    let Some(index) = index_of(app, id) else {
+        return false;
+    };
+
+    let mut todos: Vec<Todo> = std::mem::replace(&mut app.todos, Vec::new().into()).into();
+    todos.remove(index);
+    app.todos = todos.into();
+
+    notify_change(app, id);
+    true
+}
+
+/// 指定インデックスのTodoの内容から、キャッシュ用の安定したハッシュ値を計算します
+///
+/// IDとノートのバイト列に対して固定のシードでFNV-1a（64ビット）を計算するため、
+/// プロセスや実行環境が変わっても同じ内容からは同じ値が得られます。
+/// 状態、分類、優先度はハッシュに含まれません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 対象のTodoのインデックス（0から始まる）
+///
+/// # 戻り値
+///
+/// ハッシュ値を返します。インデックスが範囲外の場合は`0`を返しますが、
+/// `0`は正常なハッシュ値としてもありえるため、範囲の判定には`get_todo_count`を併用してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, todo_hash_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let note = CString::new("牛乳を買う").unwrap();
+/// let mut a = App::default();
+/// let mut b = App::default();
+/// add_todo(&mut a, 1, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut b, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(todo_hash_at(&a, 0), todo_hash_at(&b, 0));
+/// ```
+#[ffi_export]
+pub fn todo_hash_at(app: &App, index: usize) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let Some(todo) = app.todos.get(index) else {
+        return 0;
+    };
+
+    let bytes = todo
+        .id
+        .to_le_bytes()
+        .into_iter()
+        .chain(todo.note.to_str().bytes());
+    bytes.fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// 指定したIDのTodoのノートを更新します
+///
+/// 同じIDのTodoが複数ある場合は、最初の1件のみを更新します。
+/// 古いノートは新しいノートに置き換えた時点で解放されます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `id` - 更新するTodoのID
+/// * `new_note` - 新しいノート
+///
+/// # 戻り値
+///
+/// 更新した場合は`true`、該当するTodoが存在しない場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, update_todo_note};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let new_note = CString::new("卵を買う").unwrap();
+/// assert!(update_todo_note(&mut app, 1, char_p::Ref::from(new_note.as_ref())));
+/// assert_eq!(app.todos[0].note.to_str(), "卵を買う");
+/// ```
+#[ffi_export]
+pub fn update_todo_note(app: &mut App, id: i32, new_note: char_p::Ref<'_>) -> bool {
+    let Some(index) = index_of(app, id) else {
+        return false;
+    };
+
+    // 代入時に古い char_p::Box はドロップされ、メモリが解放される
+    app.todos[index].note = new_note.to_owned();
+    true
+}
+
+/// 指定した文字列を含むTodoをリストの先頭に移動します
+///
+/// 安定な分割を行うため、先頭に移動したTodo同士、残りのTodo同士の順番は変わりません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `needle` - 検索する文字列
+///
+/// # 戻り値
+///
+/// 先頭に移動したTodoの数を返します。`needle`が空文字列の場合は何もせずに`0`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, float_matching_to_top, get_todo_id_at};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "レポートを書く"), (2, "至急: 電話する")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// let needle = CString::new("至急").unwrap();
+/// assert_eq!(float_matching_to_top(&mut app, char_p::Ref::from(needle.as_ref())), 1);
+/// assert_eq!(get_todo_id_at(&app, 0), 2);
+/// ```
+#[ffi_export]
+pub fn float_matching_to_top(app: &mut App, needle: char_p::Ref<'_>) -> usize {
+    let needle = needle.to_str();
+    if needle.is_empty() {
+        return 0;
+    }
+
+    let todos: Vec<Todo> = std::mem::replace(&mut app.todos, Vec::new().into()).into();
+    let (mut matching, rest): (Vec<Todo>, Vec<Todo>) = todos
+        .into_iter()
+        .partition(|todo| todo.note.to_str().contains(needle));
+
+    let count = matching.len();
+    matching.extend(rest);
+    app.todos = matching.into();
+    count
+}
+
+/// 指定したIDのTodoの完了フラグを設定します
+///
+/// 完了にすると状態は`Status::Done`になり、未完了に戻すと`Status::Todo`になります。
+/// 同じIDのTodoが複数ある場合は、最初の1件のみを更新します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `id` - 対象のTodoのID
+/// * `done` - 完了にする場合は`true`、未完了に戻す場合は`false`
+///
+/// # 戻り値
+///
+/// 更新した場合は`true`、該当するTodoが存在しない場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, is_todo_done, set_todo_done};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert!(set_todo_done(&mut app, 1, true));
+/// assert!(is_todo_done(&app, 1));
+/// ```
+#[ffi_export]
+pub fn set_todo_done(app: &mut App, id: i32, done: bool) -> bool {
+    let Some(index) = index_of(app, id) else {
+        return false;
+    };
+
+    app.todos[index].set_done(done);
+    true
+}
+
+/// 指定したIDのTodoが完了しているかどうかを判定します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `id` - 対象のTodoのID
+///
+/// # 戻り値
+///
+/// Todoが完了している場合は`true`を返します。
+/// 未完了の場合、または該当するTodoが存在しない場合は`false`を返します。
+#[ffi_export]
+pub fn is_todo_done(app: &App, id: i32) -> bool {
+    index_of(app, id).is_some_and(|index| app.todos[index].done)
+}
+
+/// Todoをインデックスとともに順番に走査します
+///
+/// 行ごとに背景色を交互に変えるなど、インデックスを使って描画したい場合に利用します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `callback` - 各Todoに対して、インデックス（0から始まる）とともに呼び出される関数
+///   （渡されるノートへの参照は呼び出し中のみ有効）
+/// * `user_data` - `callback`にそのまま渡される任意のポインタ
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, for_each_todo_indexed};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::c_void;
+///
+/// extern "C" fn print_row(index: usize, id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) {
+///     let style = if index % 2 == 0 { "even" } else { "odd" };
+///     println!("[{}] {}: {}", style, id, note.to_str());
+/// }
+///
+/// let app = App::default();
+/// for_each_todo_indexed(&app, print_row, std::ptr::null_mut());
+/// ```
+#[ffi_export]
+pub fn for_each_todo_indexed<'app>(
+    app: &'app App,
+    callback: extern "C" fn(index: usize, id: i32, note: char_p::Ref<'app>, user_data: *mut c_void),
+    user_data: *mut c_void,
+) {
+    for (index, todo) in app.todos.iter().enumerate() {
+        callback(index, todo.id, todo.note.as_ref(), user_data);
+    }
+}
+
+/// すべてのTodoを削除します
+///
+/// 既存のTodoはノートも含めてすべて解放されます。
+/// 削除後もアプリケーションはそのまま利用できます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, clear_todos, get_todo_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// clear_todos(&mut app);
+/// assert_eq!(get_todo_count(&app), 0);
+/// ```
+#[ffi_export]
+pub fn clear_todos(app: &mut App) {
+    // 古い repr_c::Vec は通知の後にドロップされ、各 char_p::Box も解放される
+    let removed = std::mem::replace(&mut app.todos, Vec::new().into());
+    for todo in removed.iter() {
+        notify_change(app, todo.id);
+    }
+}
+
+/// 文字列全体がグロブパターンに一致するかどうかを判定します
+///
+/// `*`は0文字以上の任意の文字列、`?`は任意の1文字（バイトではなく文字）に一致します。
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // 直前の`*`の位置と、その`*`に対応させ始めたテキストの位置
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => {
+                // 直前の`*`に1文字多く対応させてやり直す
+                let Some((star, matched)) = backtrack else {
+                    return false;
+                };
+                backtrack = Some((star, matched + 1));
+                p = star + 1;
+                t = matched + 1;
+            }
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// グロブパターンに一致するノートをまとめて置き換えます
+///
+/// ノート全体がパターンに一致したTodoのノートを、`replacement`で丸ごと置き換えます。
+/// パターンでは`*`が0文字以上の任意の文字列、`?`が任意の1文字に一致します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `pattern` - ノート全体と照合するグロブパターン
+/// * `replacement` - 置き換え後のノート
+///
+/// # 戻り値
+///
+/// ノートを置き換えたTodoの数を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, replace_notes_matching_glob};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "temp: メモ"), (2, "牛乳を買う")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// let pattern = CString::new("temp*").unwrap();
+/// let replacement = CString::new("(削除済み)").unwrap();
+/// let replaced = replace_notes_matching_glob(
+///     &mut app,
+///     char_p::Ref::from(pattern.as_ref()),
+///     char_p::Ref::from(replacement.as_ref()),
+/// );
+///
+/// assert_eq!(replaced, 1);
+/// assert_eq!(app.todos[0].note.to_str(), "(削除済み)");
+/// ```
+#[ffi_export]
+pub fn replace_notes_matching_glob(
+    app: &mut App,
+    pattern: char_p::Ref<'_>,
+    replacement: char_p::Ref<'_>,
+) -> usize {
+    let pattern = pattern.to_str();
+    let mut replaced = 0;
+    for todo in app.todos.iter_mut() {
+        if glob_match(pattern, todo.note.to_str()) {
+            todo.note = replacement.to_owned();
+            replaced += 1;
+        }
+    }
+    replaced
+}
+
+/// すべてのTodoのIDに同じ値を加算します
+///
+/// 取り込んだTodoのIDを別の範囲にずらして、既存のIDとの衝突を避ける場合に利用します。
+/// 加算結果が`i32`の範囲を超える場合は`i32::MIN`または`i32::MAX`に丸められます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `delta` - 加算する値（負の値も指定可能）
+///
+/// # 戻り値
+///
+/// 範囲を超えて丸められたIDの数を返します。`0`以外の場合は、IDが重複している可能性があります。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_todo_id_at, offset_all_ids};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(offset_all_ids(&mut app, 1000), 0);
+/// assert_eq!(get_todo_id_at(&app, 0), 1001);
+/// ```
+#[ffi_export]
+pub fn offset_all_ids(app: &mut App, delta: i32) -> usize {
+    let mut saturated = 0;
+    for todo in app.todos.iter_mut() {
+        todo.id = match todo.id.checked_add(delta) {
+            Some(id) => id,
+            None => {
+                saturated += 1;
+                todo.id.saturating_add(delta)
+            }
+        };
+    }
+    saturated
+}
+
+/// 指定インデックスのTodoと同じノートを持つ、他のTodoのIDを取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 基準となるTodoのインデックス（0から始まる）
+///
+/// # 戻り値
+///
+/// `index`のTodoとノートが完全に一致するTodoのIDをリストの順番で返します。
+/// `index`のTodo自身は含まれません。インデックスが範囲外の場合は空のベクタを返します。
+/// 返されたベクタは`free_i32_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, ids_with_same_note_as};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "牛乳"), (2, "卵"), (3, "牛乳")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(&ids_with_same_note_as(&app, 0)[..], &[3]);
+/// ```
+#[ffi_export]
+pub fn ids_with_same_note_as(app: &App, index: usize) -> repr_c::Vec<i32> {
+    let Some(target) = app.todos.get(index) else {
+        return Vec::new().into();
+    };
+    let note = target.note.to_str();
+
+    app.todos
+        .iter()
+        .enumerate()
+        .filter(|&(i, todo)| i != index && todo.note.to_str() == note)
+        .map(|(_, todo)| todo.id)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// 指定したIDのTodoのインデックスを取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `id` - 検索するTodoのID
+///
+/// # 戻り値
+///
+/// 該当するTodoのインデックス（0から始まる）を返します。
+/// 同じIDのTodoが複数ある場合は最初の1件のインデックスを返し、
+/// 該当するTodoが存在しない場合は`-1`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, find_todo_index_by_id};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in [10, 20] {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(find_todo_index_by_id(&app, 20), 1);
+/// assert_eq!(find_todo_index_by_id(&app, 30), -1);
+/// ```
+#[ffi_export]
+pub fn find_todo_index_by_id(app: &App, id: i32) -> isize {
+    index_of(app, id).map_or(-1, |index| index as isize)
+}
+
+/// 1つ目のアプリケーションにのみ存在するIDを取得します
+///
+/// # 引数
+///
+/// * `a` - 比較元のアプリケーションインスタンスへの参照
+/// * `b` - 比較先のアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// `a`に存在し、`b`に存在しないIDを`a`の順番で返します。
+/// `a`に同じIDのTodoが複数ある場合も1回だけ返します。
+/// 返されたベクタは`free_i32_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, ids_only_in_first};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let note = CString::new("タスク").unwrap();
+/// let mut a = App::default();
+/// let mut b = App::default();
+/// for id in [1, 2, 3] {
+///     add_todo(&mut a, id, char_p::Ref::from(note.as_ref()));
+/// }
+/// add_todo(&mut b, 2, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(&ids_only_in_first(&a, &b)[..], &[1, 3]);
+/// ```
+#[ffi_export]
+pub fn ids_only_in_first(a: &App, b: &App) -> repr_c::Vec<i32> {
+    let ids = b.todos.iter().map(|todo| todo.id).collect::<Vec<_>>();
+    extra_ids(a, ids[..].into())
+}
+
+/// アプリケーション全体をJSONに変換します
+///
+/// Todoリストを保存したい場合に利用します。`serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// `[{"id":1,"note":"...","status":"todo","done":false}]`の形式のJSON配列を、
+/// リストの順番で返します。分類は空でない場合のみ`"category"`、優先度は`medium`以外の
+/// 場合のみ`"priority"`として出力されます。ノート中の`"`、`\`、制御文字はエスケープされ、
+/// 日本語などの非ASCII文字はUTF-8のまま出力されます。
+/// 返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, app_to_json};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(
+///     app_to_json(&app).to_str(),
+///     r#"[{"id":1,"note":"牛乳を買う","status":"todo","done":false}]"#
+/// );
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn app_to_json(app: &App) -> char_p::Box {
+    owned_string(serde_json::to_string(&app.to_records()).unwrap())
+}
+
+/// アプリケーションがJSONを経由して欠落なく復元できるかどうかを確認します
+///
+/// アプリケーションを一度JSONに変換してから読み込み直し、元の内容と比較します。
+/// 特殊な文字を含むノートでシリアライズの不具合が起きていないかの自己診断に利用します。
+/// `serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 読み込み直した結果が、IDやノート、並び順を含めて元のアプリケーションと一致する場合は
+/// `true`、一致しない場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, json_roundtrip_ok};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("\"引用\"と\\記号\\").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert!(json_roundtrip_ok(&app));
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn json_roundtrip_ok(app: &App) -> bool {
+    let Ok(json) = serde_json::to_string(&app.to_records()) else {
+        return false;
+    };
+
+    serde_json::from_str(&json)
+        .ok()
+        .and_then(App::from_records)
+        .is_some_and(|restored| restored.todos[..] == app.todos[..])
+}
+
+/// JSONからアプリケーションを復元します
+///
+/// `app_to_json`で出力した形式のJSON配列を読み込みます。
+/// `status`を省略したTodoは`todo`、`done`を省略したTodoは`false`、
+/// `priority`を省略したTodoは`medium`として扱います。
+/// `serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `json` - Todoの配列を表すJSON文字列
+///
+/// # 戻り値
+///
+/// 復元したアプリケーションを返します。JSONを解析できない場合や、
+/// ノートにNULが含まれる場合は空のアプリケーションを返します。
+/// 返されたアプリケーションは`app_free`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{app_from_json, get_todo_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let json = CString::new(r#"[{"id":1,"note":"牛乳を買う"}]"#).unwrap();
+/// let app = app_from_json(char_p::Ref::from(json.as_ref()));
+///
+/// assert_eq!(get_todo_count(&app), 1);
+/// assert!(!app.todos[0].done);
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn app_from_json(json: char_p::Ref<'_>) -> repr_c::Box<App> {
+    let app = serde_json::from_str(json.to_str())
+        .ok()
+        .and_then(App::from_records)
+        .unwrap_or_default();
+
+    Box::new(app).into()
+}
+
+/// 英数字と空白のみからなるノートの場合だけTodoを追加します
+///
+/// 入力を厳しく制限したい場合に利用します。英数字はUnicodeの定義に従うため、
+/// 漢字やひらがななども使用できます。
+///
+/// # 引数
+///
+/// * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+/// * `id` - 追加するTodoの一意識別子
+/// * `note` - Todoの内容を表す文字列
+///
+/// # 戻り値
+///
+/// 追加に成功した場合は`true`を返します。ノートに英数字と半角スペース以外の文字
+/// （記号や改行など）が含まれる場合は追加せずに`false`を返します。
+/// その他の失敗条件は`add_todo`と同じです。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo_alnum_only};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let valid = CString::new("Buy milk 2").unwrap();
+/// let invalid = CString::new("Buy milk!").unwrap();
+///
+/// assert!(add_todo_alnum_only(&mut app, 1, char_p::Ref::from(valid.as_ref())));
+/// assert!(!add_todo_alnum_only(&mut app, 2, char_p::Ref::from(invalid.as_ref())));
+/// ```
+#[ffi_export]
+pub fn add_todo_alnum_only(app: &mut App, id: i32, note: char_p::Ref<'_>) -> bool {
+    let allowed = note
+        .to_str()
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == ' ');
+    if !allowed {
+        return false;
+    }
+
+    add_todo(app, id, note)
+}
+
+/// 複数のTodoをまとめて追加します
+///
+/// FFIの呼び出しを1回で済ませ、リストの再確保も1回にまとめます。
+/// `ids`と`notes`の同じ位置の要素から1件のTodoを作成します。
+///
+/// # 引数
+///
+/// * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+/// * `ids` - 追加するTodoのIDの配列
+/// * `notes` - 追加するTodoのノートの配列
+///
+/// # 戻り値
+///
+/// 実際に追加したTodoの数を返します。`ids`と`notes`の長さが異なる場合は、
+/// 短い方の長さまでを追加します。`add_todo`と同様に、既に存在するIDや
+/// 同じ呼び出しの中で先に追加したIDと重複するTodoは追加されません。
+/// `set_max_todos`で上限を設定している場合は、上限に達した時点で残りを追加しません。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todos, get_todo_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let milk = CString::new("牛乳を買う").unwrap();
+/// let egg = CString::new("卵を買う").unwrap();
+/// let notes = [char_p::Ref::from(milk.as_ref()), char_p::Ref::from(egg.as_ref())];
+///
+/// assert_eq!(add_todos(&mut app, [1, 2][..].into(), notes[..].into()), 2);
+/// assert_eq!(get_todo_count(&app), 2);
+/// ```
+#[ffi_export]
+pub fn add_todos(
+    app: &mut App,
+    ids: c_slice::Ref<'_, i32>,
+    notes: c_slice::Ref<'_, char_p::Ref<'_>>,
+) -> usize {
+    let mut seen: HashSet<i32> = app.todos.iter().map(|todo| todo.id).collect();
+    let new_todos: Vec<Todo> = ids
+        .iter()
+        .zip(notes.iter())
+        .filter(|(id, _)| seen.insert(**id))
+        .filter_map(|(&id, note)| Todo::try_new(id, note.to_str()).ok())
+        .take(remaining_slots(app))
+        .collect();
+    let added_ids: Vec<i32> = new_todos.iter().map(|todo| todo.id).collect();
+
+    let mut todos: Vec<Todo> = std::mem::replace(&mut app.todos, Vec::new().into()).into();
+    todos.extend(new_todos);
+    app.todos = todos.into();
+    for &id in &added_ids {
+        notify_change(app, id);
+    }
+    added_ids.len()
+}
+
+/// すべてのノートに含まれる単語の総数を取得します
+///
+/// 単語は空白文字で区切って数えます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 各ノートの単語数の合計を返します。Todoが存在しない場合は0を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, total_word_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// for (id, note) in [(1, "buy some milk"), (2, "call mom")] {
+///     let note = CString::new(note).unwrap();
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(total_word_count(&app), 5);
+/// ```
+#[ffi_export]
+pub fn total_word_count(app: &App) -> usize {
+    app.todos
+        .iter()
+        .map(|todo| todo.note.to_str().split_whitespace().count())
+        .sum()
+}
+
+/// シード値から決定的に選んだTodoのインデックスを取得します
+///
+/// 復習用に1件を選ぶ場合など、再現性のある「ランダムな」選択に利用します。
+/// 内部ではSplitMix64で`seed`から擬似乱数を生成しています。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `seed` - 擬似乱数のシード値
+///
+/// # 戻り値
+///
+/// `0`以上Todoの数未満のインデックスを返します。同じシード値とTodoの数からは常に同じ
+/// インデックスが得られます。Todoが存在しない場合は`-1`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, pick_index};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in 1..=5 {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// let index = pick_index(&app, 42);
+/// assert!((0..5).contains(&index));
+/// assert_eq!(pick_index(&app, 42), index);
+/// ```
+#[ffi_export]
+pub fn pick_index(app: &App, seed: u64) -> isize {
+    if app.todos.is_empty() {
+        return -1; // エラー値
+    }
+
+    // SplitMix64
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+
+    (z % app.todos.len() as u64) as isize
+}
+
+/// IDを自動で割り当ててTodoを追加します
+///
+/// 呼び出し側でIDを管理せずにTodoを追加したい場合に利用します。
+/// `app.next_id`から順に未使用のIDを探して割り当て、`next_id`をその次の値に進めます。
+/// `next_id`は削除によって巻き戻らないため、削除したTodoのIDが再利用されることはありません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `note` - Todoの内容を表す文字列への参照
+///
+/// # 戻り値
+///
+/// 割り当てたIDを返します。割り当て可能なIDが残っていない場合は`-1`を返し、
+/// Todoは追加されません。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo_auto};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+///
+/// assert_eq!(add_todo_auto(&mut app, char_p::Ref::from(note.as_ref())), 1);
+/// assert_eq!(add_todo_auto(&mut app, char_p::Ref::from(note.as_ref())), 2);
+/// ```
+#[ffi_export]
+pub fn add_todo_auto(app: &mut App, note: char_p::Ref<'_>) -> i32 {
+    // `add_todo`で明示的に使われたIDは飛ばす
+    let mut id = app.next_id;
+    while index_of(app, id).is_some() {
+        let Some(next) = id.checked_add(1) else {
+            return -1; // エラー値
+        };
+        id = next;
+    }
+    let Some(next_id) = id.checked_add(1) else {
+        return -1; // エラー値
+    };
+
+    if add_todo_checked(app, id, note) != AddResult::Ok {
+        return -1; // エラー値
+    }
+    app.next_id = next_id;
+    id
+}
+
+/// `app_save_versioned`で書き込むバイナリ形式のスキーマバージョン
+///
+/// * `1` - Todoのすべてのフィールド（`encode_todo_stream`の形式）
+///
+/// 形式を変更した場合は、この値を更新し、`decode_versioned`で以前のバージョンも読めるようにしてください。
+const APP_SCHEMA_VERSION: u16 = 1;
+
+/// アプリケーションを先頭にスキーマバージョンを付けたバイナリ形式に変換します
+fn encode_versioned(app: &App) -> Vec<u8> {
+    let mut out = APP_SCHEMA_VERSION.to_le_bytes().to_vec();
+    encode_todo_stream(app, &mut out);
+    out
+}
+
+/// スキーマバージョン付きのバイナリ形式からアプリケーションを組み立てます
+///
+/// バージョンが未知の場合や、データが不正な場合は`None`を返します。
+fn decode_versioned(data: &[u8]) -> Option<App> {
+    let (version, rest) = data.split_first_chunk()?;
+    match u16::from_le_bytes(*version) {
+        1 => decode_todo_stream(rest),
+        _ => None,
+    }
+}
+
+/// アプリケーションをスキーマバージョン付きのバイナリ形式でファイルに保存します
+///
+/// 先頭2バイトにスキーマバージョン（リトルエンディアンの`u16`）を書き込み、
+/// 続けてID、ノート、状態、完了フラグ、分類、優先度を含むすべてのTodoを書き込みます。
+/// ファイルが既に存在する場合は上書きします。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `path` - 保存先のファイルパス
+///
+/// # 戻り値
+///
+/// 保存できた場合は`true`、ファイルへの書き込みに失敗した場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, app_load_versioned, app_save_versioned};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let path = std::env::temp_dir().join("safer_ffi_example_doc_save_versioned.bin");
+/// let c_path = CString::new(path.to_str().unwrap()).unwrap();
+///
+/// assert!(app_save_versioned(&app, char_p::Ref::from(c_path.as_ref())));
+/// let restored = app_load_versioned(char_p::Ref::from(c_path.as_ref()));
+/// assert_eq!(restored.todos[0].note.to_str(), "牛乳を買う");
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[ffi_export]
+pub fn app_save_versioned(app: &App, path: char_p::Ref<'_>) -> bool {
+    std::fs::write(path.to_str(), encode_versioned(app)).is_ok()
+}
+
+/// `app_save_versioned`で保存したファイルからアプリケーションを読み込みます
+///
+/// # 引数
+///
+/// * `path` - 読み込むファイルのパス
+///
+/// # 戻り値
+///
+/// 読み込んだアプリケーションを返します。ファイルを読み込めない場合、スキーマバージョンが
+/// 未知のバージョンの場合、データが不正な場合は空のアプリケーションを返します。
+/// 返されたアプリケーションは`app_free`で解放してください。
+#[ffi_export]
+pub fn app_load_versioned(path: char_p::Ref<'_>) -> repr_c::Box<App> {
+    let app = std::fs::read(path.to_str())
+        .ok()
+        .and_then(|data| decode_versioned(&data))
+        .unwrap_or_default();
+    Box::new(app).into()
+}
+
+/// すべてのTodoのIDを1つのスライスとして取得します
+///
+/// `get_todo_id_at`をTodoの数だけ呼び出す代わりに、1回の呼び出しでIDの一覧を取得できます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// Todoの順番どおりのIDのスライスを返します。Todoが存在しない場合は長さ0のスライスを返し、
+/// ポインタはNULLになりません。返されたスライスは`free_id_slice`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, free_id_slice, get_all_todo_ids};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 3, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let ids = get_all_todo_ids(&app);
+/// assert_eq!(&ids[..], &[3, 1]);
+/// free_id_slice(ids);
+/// ```
+#[ffi_export]
+pub fn get_all_todo_ids(app: &App) -> c_slice::Box<i32> {
+    let ids: Box<[i32]> = app.todos.iter().map(|todo| todo.id).collect();
+    ids.into()
+}
+
+/// `get_all_todo_ids`で取得したスライスのメモリを解放します
+///
+/// # 引数
+///
+/// * `_s` - 解放するスライス
+#[ffi_export]
+pub fn free_id_slice(_s: c_slice::Box<i32>) {
+    // c_slice::Box はドロップ時に自動的にメモリを解放します
+}
+
+/// 最も多く出現するIDとその出現回数を取得します
+///
+/// IDの重複を検出する用途を想定しています。出現回数が同じIDが複数ある場合は、
+/// 最も小さいIDを選びます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `out_id` - 最も多く出現するIDの書き込み先
+/// * `out_count` - 出現回数の書き込み先
+///
+/// # 戻り値
+///
+/// 成功した場合は`true`を返します。Todoが存在しない場合は`false`を返し、
+/// `out_id`と`out_count`は変更されません。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Todo, most_frequent_id};
+///
+/// let mut app = App::default();
+/// app.todos = vec![Todo::new(2, "a"), Todo::new(1, "b"), Todo::new(2, "c")].into();
+///
+/// let (mut id, mut count) = (0, 0);
+/// assert!(most_frequent_id(&app, &mut id, &mut count));
+/// assert_eq!((id, count), (2, 2));
+/// ```
+#[ffi_export]
+pub fn most_frequent_id(app: &App, out_id: &mut i32, out_count: &mut usize) -> bool {
+    let mut counts: HashMap<i32, usize> = HashMap::new();
+    for todo in app.todos.iter() {
+        *counts.entry(todo.id).or_default() += 1;
+    }
+
+    // 出現回数が多い順、同数の場合はIDが小さい順
+    let Some((id, count)) = counts
+        .into_iter()
+        .max_by_key(|&(id, count)| (count, std::cmp::Reverse(id)))
+    else {
+        return false;
+    };
+
+    *out_id = id;
+    *out_count = count;
+    true
+}
+
+/// TodoをIDでソートします
+///
+/// `app.todos`の並び順そのものを変更します。安定ソートのため、同じIDのTodoは
+/// ソート前の順番のまま並びます。並び順を変えずに走査したい場合は
+/// `for_each_todo_sorted_by_id`を利用してください。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `ascending` - `true`の場合は昇順、`false`の場合は降順
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Todo, sort_todos_by_id};
+///
+/// let mut app = App::default();
+/// app.todos = vec![Todo::new(3, "c"), Todo::new(1, "a"), Todo::new(2, "b")].into();
+///
+/// sort_todos_by_id(&mut app, true);
+/// assert_eq!(app.todos.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+/// ```
+#[ffi_export]
+pub fn sort_todos_by_id(app: &mut App, ascending: bool) {
+    // 安定ソートなので同じIDのTodoは元の順番のまま
+    if ascending {
+        app.todos.sort_by_key(|todo| todo.id);
+    } else {
+        app.todos.sort_by_key(|todo| std::cmp::Reverse(todo.id));
+    }
+}
+
+/// すべてのノートの前後の空白を取り除きます
+///
+/// 空白の判定は`str::trim`と同じく、Unicodeの空白文字（全角スペースを含む）を対象とします。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+///
+/// # 戻り値
+///
+/// 実際に内容が変わったノートの数を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Todo, trim_all_notes};
+///
+/// let mut app = App::default();
+/// app.todos = vec![Todo::new(1, "  牛乳を買う\n"), Todo::new(2, "レポート")].into();
+///
+/// assert_eq!(trim_all_notes(&mut app), 1);
+/// assert_eq!(app.todos[0].note.to_str(), "牛乳を買う");
+/// ```
+#[ffi_export]
+pub fn trim_all_notes(app: &mut App) -> usize {
+    let mut changed = 0;
+    for todo in app.todos.iter_mut() {
+        let note = todo.note.to_str();
+        let trimmed = note.trim();
+        if trimmed.len() == note.len() {
+            continue;
+        }
+
+        // 元のノートの一部なのでNULバイトは含まれない
+        todo.note = trimmed.to_string().try_into().unwrap();
+        changed += 1;
+    }
+    changed
+}
+
+/// Todoをノートの内容でソートします
+///
+/// `app.todos`の並び順そのものを変更します。比較は`&str`の順序（UTF-8のバイト順、
+/// すなわちUnicodeのコードポイント順）で行い、ロケールに応じた照合順序ではありません。
+/// そのため大文字は小文字より前に並び、ひらがなはカタカナより前、漢字はさらに後ろに並びます。
+/// 安定ソートのため、同じノートのTodoはソート前の順番のまま並びます。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `ascending` - `true`の場合は昇順、`false`の場合は降順
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Todo, sort_todos_by_note};
+///
+/// let mut app = App::default();
+/// app.todos = vec![Todo::new(1, "りんご"), Todo::new(2, "banana"), Todo::new(3, "Apple")].into();
+///
+/// sort_todos_by_note(&mut app, true);
+/// assert_eq!(app.todos.iter().map(|t| t.id).collect::<Vec<_>>(), vec![3, 2, 1]);
+/// ```
+#[ffi_export]
+pub fn sort_todos_by_note(app: &mut App, ascending: bool) {
+    if ascending {
+        app.todos
+            .sort_by(|a, b| a.note.to_str().cmp(b.note.to_str()));
+    } else {
+        app.todos
+            .sort_by(|a, b| b.note.to_str().cmp(a.note.to_str()));
+    }
+}
+
+/// ノートに指定した文字列を含むTodoだけを集めた新しいアプリケーションを取得します
+///
+/// 元のアプリケーションは変更されません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `substring` - 検索する文字列（空文字列はすべてのTodoに一致します）
+///
+/// # 戻り値
+///
+/// 一致したTodoを元の順番のままコピーした新しいAppを返します。一致するTodoがない場合は
+/// 空のAppを返します。返されたAppは`app_free`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, app_free, filter_todos_contains};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let milk = CString::new("牛乳を買う").unwrap();
+/// let report = CString::new("レポートを書く").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(milk.as_ref()));
+/// add_todo(&mut app, 2, char_p::Ref::from(report.as_ref()));
+///
+/// let substring = CString::new("買う").unwrap();
+/// let filtered = filter_todos_contains(&app, char_p::Ref::from(substring.as_ref()));
+/// assert_eq!(filtered.todos.len(), 1);
+/// assert_eq!(filtered.todos[0].id, 1);
+/// app_free(filtered);
+/// ```
+#[ffi_export]
+pub fn filter_todos_contains(app: &App, substring: char_p::Ref<'_>) -> repr_c::Box<App> {
+    let substring = substring.to_str();
+    let todos: Vec<Todo> = app
+        .todos
+        .iter()
+        .filter(|todo| todo.note.to_str().contains(substring))
+        .cloned()
+        .collect();
+
+    Box::new(App {
+        todos: todos.into(),
+        ..Default::default()
+    })
+    .into()
+}
+
+/// インデックスからノートへの対応をJSONオブジェクト文字列として取得します
+///
+/// クライアント側でインデックスをキーにしてノートを参照したい場合に利用します。
+/// `serde`機能が有効な場合のみ利用可能です。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// `{"0":"...","1":"..."}`の形式で、インデックスを文字列にしたキーとノートを対応させた
+/// JSONオブジェクトを返します。キーはインデックスの昇順に並び、Todoが存在しない場合は
+/// `{}`を返します。返された文字列は`free_string`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, notes_by_index_json};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 7, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(notes_by_index_json(&app).to_str(), r#"{"0":"牛乳を買う"}"#);
+/// ```
+#[cfg(feature = "serde")]
+#[ffi_export]
+pub fn notes_by_index_json(app: &App) -> char_p::Box {
+    // 整数のキーは文字列として出力され、"10"が"2"より前に並ぶこともない
+    let notes: std::collections::BTreeMap<usize, &str> = app
+        .todos
+        .iter()
+        .enumerate()
+        .map(|(index, todo)| (index, todo.note.to_str()))
+        .collect();
+
+    owned_string(serde_json::to_string(&notes).unwrap())
+}
+
+/// 複数のスレッドから同時に利用できるTodoアプリケーション
+///
+/// 内部の`App`を`Mutex`で保護し、`sync_`で始まる関数は呼び出しごとにロックを取得します。
+/// Goの複数のgoroutineから同じアプリケーションを操作する場合は、`App`と`add_todo`などの
+/// 代わりにこの型と`sync_`で始まる関数を利用してください。`App`を直接操作する関数は
+/// 同期を行わないため、同時に呼び出すとデータ競合が発生します。
+#[derive_ReprC]
+#[repr(opaque)]
+pub struct SyncApp {
+    inner: std::sync::Mutex<App>,
+}
+
+impl SyncApp {
+    /// 内部の`App`のロックを取得します
+    ///
+    /// 別のスレッドがロック中にパニックした場合も、そのまま`App`を利用します。
+    fn lock(&self) -> std::sync::MutexGuard<'_, App> {
+        self.inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// 複数のスレッドから利用できる空のアプリケーションを作成します
+///
+/// # 戻り値
+///
+/// 新しいSyncAppインスタンスを返します。不要になったら`sync_app_free`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{sync_add_todo, sync_app_free, sync_app_new, sync_get_todo_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let app = sync_app_new();
+/// let note = CString::new("牛乳を買う").unwrap();
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| sync_add_todo(&app, 1, char_p::Ref::from(note.as_ref())));
+///     s.spawn(|| sync_add_todo(&app, 2, char_p::Ref::from(note.as_ref())));
+/// });
+///
+/// assert_eq!(sync_get_todo_count(&app), 2);
+/// sync_app_free(app);
+/// ```
+#[ffi_export]
+pub fn sync_app_new() -> repr_c::Box<SyncApp> {
+    Box::new(SyncApp {
+        inner: std::sync::Mutex::new(App::default()),
+    })
+    .into()
+}
+
+/// ロックを取得してTodoを追加します
+///
+/// `add_todo`の、複数のスレッドから同時に呼び出せる版です。
+///
+/// # 引数
+///
+/// * `app` - SyncAppインスタンスへの参照
+/// * `id` - Todo項目の識別子
+/// * `note` - Todoの内容を表す文字列への参照
+///
+/// # 戻り値
+///
+/// 追加した場合は`true`、同じIDのTodoが既に存在する場合は`false`を返します。
+#[ffi_export]
+pub fn sync_add_todo(app: &SyncApp, id: i32, note: char_p::Ref<'_>) -> bool {
+    add_todo(&mut app.lock(), id, note)
+}
+
+/// ロックを取得してTodoの数を取得します
+///
+/// `get_todo_count`の、複数のスレッドから同時に呼び出せる版です。
+///
+/// # 引数
+///
+/// * `app` - SyncAppインスタンスへの参照
+///
+/// # 戻り値
+///
+/// Todoの数を返します。
+#[ffi_export]
+pub fn sync_get_todo_count(app: &SyncApp) -> usize {
+    get_todo_count(&app.lock())
+}
+
+/// SyncAppのメモリを解放します
+///
+/// 他のスレッドが利用中のSyncAppを解放しないでください。
+///
+/// # 引数
+///
+/// * `_app` - 解放するSyncAppインスタンス
+#[ffi_export]
+pub fn sync_app_free(_app: repr_c::Box<SyncApp>) {
+    // repr_c::Box はドロップ時に自動的にメモリを解放します
+}
+
+/// 指定インデックスのTodoのノートを、呼び出し側が用意したバッファにコピーします
+///
+/// `get_todo_note_at`と異なりメモリを確保しないため、描画ループなどで繰り返し
+/// ノートを読み出す場合に利用します。ノートのUTF-8バイト列とNUL終端を書き込み、
+/// バッファが足りない場合は何も書き込みません。切り詰めてでも書き込みたい場合は
+/// `copy_todo_note_truncated_at`を利用してください。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `index` - 取得するTodoのインデックス（0から始まる）
+/// * `buf` - 書き込み先のバッファ（NUL終端の1バイトを含めた長さ）
+///
+/// # 戻り値
+///
+/// 書き込んだバイト数（`copy_todo_note_truncated_at`と同じくNUL終端を含まない）を返します。
+/// バッファがノートとNUL終端を格納するのに足りない場合は`-1`、インデックスが範囲外の場合は
+/// `-2`を返し、いずれの場合もバッファは変更されません。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, get_todo_note_at_into};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let mut buf = [0u8; 16];
+/// assert_eq!(get_todo_note_at_into(&app, 0, (&mut buf[..]).into()), 6);
+/// assert_eq!(&buf[..7], "牛乳\0".as_bytes());
+/// ```
+#[ffi_export]
+pub fn get_todo_note_at_into(app: &App, index: usize, buf: c_slice::Mut<'_, u8>) -> isize {
+    let Some(todo) = app.todos.get(index) else {
+        return -2; // エラー値
+    };
+
+    let note = todo.note.to_str().as_bytes();
+    if buf.len() <= note.len() {
+        return -1; // エラー値
+    }
+
+    let buf = buf.as_slice();
+    buf[..note.len()].copy_from_slice(note);
+    buf[note.len()] = 0;
+    note.len() as isize
+}
+
+/// Todoの追加・削除時に呼び出されるコールバックを設定します
+///
+/// リアクティブなUIなどで、変更に合わせて表示を更新したい場合に利用します。
+/// コールバックは、Todoを追加・削除するすべての関数から、追加・削除されたTodoごとに
+/// そのIDを引数として呼び出されます。`add_todos`や`clear_todos`などの一括で変更する関数では
+/// Todoの数だけ呼び出され、`app_restore`と`tx_commit`では変更前との差分のIDだけが
+/// 呼び出されます。並び替えやノートの変更など、Todoの増減を伴わない変更では呼び出されません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `cb` - 呼び出すコールバック（NULLを渡すと設定を解除します）
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, set_on_change};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// extern "C" fn on_change(id: i32) {
+///     println!("Todo {} が変更されました", id);
+/// }
+///
+/// let mut app = App::default();
+/// set_on_change(&mut app, Some(on_change));
+///
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+/// ```
+#[ffi_export]
+pub fn set_on_change(app: &mut App, cb: OnChangeCallback) {
+    app.on_change = cb;
+}
+
+/// キーと値を組み合わせたノートのTodoを追加します
+///
+/// ノートは`<key>: <value>`の形式で保存されます。ホスト側で文字列を連結せずに
+/// 整形済みのノートを追加したい場合に利用します。`key`と`value`はそれぞれ最初のNULバイトで
+/// 終わるため、組み立てたノートにNULバイトが含まれることはありません。
+///
+/// # 引数
+///
+/// * `app` - Todoを追加するアプリケーションインスタンスへの可変参照
+/// * `id` - 追加するTodoの一意識別子
+/// * `key` - ノートの先頭に置くキー
+/// * `value` - キーに続けて置く値
+///
+/// # 戻り値
+///
+/// 追加が成功した場合は`true`を返します。同じIDのTodoが既に存在する場合や、
+/// Todoの数が`set_max_todos`で設定した上限に達している場合は追加せずに`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo_kv};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let key = CString::new("買い物").unwrap();
+/// let value = CString::new("牛乳").unwrap();
+/// assert!(add_todo_kv(
+///     &mut app,
+///     1,
+///     char_p::Ref::from(key.as_ref()),
+///     char_p::Ref::from(value.as_ref()),
+/// ));
+/// assert_eq!(app.todos[0].note.to_str(), "買い物: 牛乳");
+/// ```
+#[ffi_export]
+pub fn add_todo_kv(app: &mut App, id: i32, key: char_p::Ref<'_>, value: char_p::Ref<'_>) -> bool {
+    let note = format!("{}: {}", key.to_str(), value.to_str());
+    add_todo_str(app, id, &note) == AddResult::Ok
+}
+
+/// 指定したIDのTodoが存在するかどうかを判定します
+///
+/// Todoを追加する前に、IDが既に使われているかを確認する場合に利用します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `id` - 確認するTodoのID
+///
+/// # 戻り値
+///
+/// 同じIDのTodoが1件以上存在する場合は`true`、存在しない場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, has_todo};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// assert!(!has_todo(&app, 1));
+///
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+/// assert!(has_todo(&app, 1));
+/// ```
+#[ffi_export]
+pub fn has_todo(app: &App, id: i32) -> bool {
+    index_of(app, id).is_some()
+}
+
+/// アプリケーションの現在の内容をスナップショットとして取得します
+///
+/// `app_save_versioned`と同じスキーマバージョン付きのバイナリ形式で、メモリ上に保存します。
+/// `app_restore`と組み合わせて、変更前の状態に戻すチェックポイントとして利用します。
+/// ID、ノート、状態、完了フラグ、分類、優先度を含むすべてのTodoを保存します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// スナップショットのバイト列を返します。内容は不透明なデータとして扱い、
+/// `app_restore`にそのまま渡してください。返されたベクタは`free_u8_vec`で解放してください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, app_restore, app_snapshot, get_todo_count};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// let snapshot = app_snapshot(&app);
+/// add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+///
+/// assert!(app_restore(&mut app, snapshot[..].into()));
+/// assert_eq!(get_todo_count(&app), 1);
+/// ```
+#[ffi_export]
+pub fn app_snapshot(app: &App) -> repr_c::Vec<u8> {
+    encode_versioned(app).into()
+}
+
+/// `app_snapshot`で取得したスナップショットの内容にアプリケーションを戻します
+///
+/// Todoの一覧のみを置き換え、`next_id`、`on_change`、`max_todos`はそのまま維持します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `blob` - `app_snapshot`で取得したバイト列
+///
+/// # 戻り値
+///
+/// 復元した場合は`true`を返します。スナップショットが不正な場合、スキーマバージョンが
+/// 未知の場合、Todoの数が`set_max_todos`で設定した上限を超える場合は`false`を返し、
+/// アプリケーションは変更されません。
+#[ffi_export]
+pub fn app_restore(app: &mut App, blob: c_slice::Ref<'_, u8>) -> bool {
+    let Some(restored) = decode_versioned(blob.as_slice()) else {
+        return false;
+    };
+    if app.max_todos != 0 && restored.todos.len() > app.max_todos {
+        return false;
+    }
+
+    let before = std::mem::replace(&mut app.todos, restored.todos);
+    notify_net_change(app, &before);
+    true
+}
+
+/// 指定したIDのTodoの優先度を設定します
+///
+/// 同じIDのTodoが複数ある場合は、最初の1件のみを更新します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `id` - 対象のTodoのID
+/// * `priority` - 設定する優先度
+///
+/// # 戻り値
+///
+/// 設定した場合は`true`、該当するTodoが存在しない場合は`false`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Priority, add_todo, get_todo_priority, set_todo_priority};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert_eq!(get_todo_priority(&app, 1), Priority::Medium);
+/// assert!(set_todo_priority(&mut app, 1, Priority::High));
+/// assert_eq!(get_todo_priority(&app, 1), Priority::High);
+/// ```
+#[ffi_export]
+pub fn set_todo_priority(app: &mut App, id: i32, priority: Priority) -> bool {
+    let Some(index) = index_of(app, id) else {
+        return false;
+    };
+
+    app.todos[index].priority = priority;
+    true
+}
+
+/// 複数のIDのTodoの優先度をまとめて設定します
+///
+/// `set_todo_priority`と異なり、同じIDのTodoが複数ある場合はそのすべてを更新します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `ids` - 優先度を設定するTodoのIDの配列（存在しないIDは無視されます）
+/// * `priority` - 設定する優先度
+///
+/// # 戻り値
+///
+/// 優先度を設定したTodoの数を返します。`ids`に同じIDが複数含まれていても、
+/// 1件のTodoは1回だけ数えます。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Priority, add_todo, get_todo_priority, set_priority_by_ids};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in 1..=3 {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+///
+/// assert_eq!(set_priority_by_ids(&mut app, [1, 3][..].into(), Priority::High), 2);
+/// assert_eq!(get_todo_priority(&app, 2), Priority::Medium);
+/// assert_eq!(get_todo_priority(&app, 3), Priority::High);
+/// ```
+#[ffi_export]
+pub fn set_priority_by_ids(app: &mut App, ids: c_slice::Ref<'_, i32>, priority: Priority) -> usize {
+    let ids: HashSet<i32> = ids.iter().copied().collect();
+    let mut updated = 0;
+    for todo in app.todos.iter_mut().filter(|todo| ids.contains(&todo.id)) {
+        todo.priority = priority;
+        updated += 1;
+    }
+    updated
+}
+
+/// 指定したIDのTodoの優先度を取得します
+///
+/// 同じIDのTodoが複数ある場合は、最初の1件の優先度を返します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `id` - 対象のTodoのID
+///
+/// # 戻り値
+///
+/// Todoの優先度を返します。該当するTodoが存在しない場合は既定値の`Priority::Medium`を
+/// 返すため、存在するかどうかを区別したい場合は`has_todo`と組み合わせてください。
+#[ffi_export]
+pub fn get_todo_priority(app: &App, id: i32) -> Priority {
+    index_of(app, id)
+        .map(|index| app.todos[index].priority)
+        .unwrap_or_default()
+}
+
+/// 指定した優先度のTodoのうち、完了しているものの割合を取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `priority` - 集計する優先度
+///
+/// # 戻り値
+///
+/// 指定した優先度のTodoに占める完了したTodoの割合（0.0〜1.0）を返します。
+/// 指定した優先度のTodoが存在しない場合は`0.0`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Priority, add_todo, priority_completion_rate, set_todo_done};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+/// set_todo_done(&mut app, 1, true);
+///
+/// assert_eq!(priority_completion_rate(&app, Priority::Medium), 0.5);
+/// assert_eq!(priority_completion_rate(&app, Priority::High), 0.0);
+/// ```
+#[ffi_export]
+pub fn priority_completion_rate(app: &App, priority: Priority) -> f64 {
+    let (total, done) = app
+        .todos
+        .iter()
+        .filter(|todo| todo.priority == priority)
+        .fold((0usize, 0usize), |(total, done), todo| {
+            (total + 1, done + usize::from(todo.done))
+        });
+
+    if total == 0 {
+        return 0.0;
+    }
+    done as f64 / total as f64
+}
+
+/// 指定した文字を含むノートの数を取得します
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `ch` - 検索する文字のUnicodeスカラー値（例: `'あ'`は`0x3042`）
+///
+/// # 戻り値
+///
+/// `ch`を1回以上含むノートの数を返します。1つのノートに複数回含まれていても1件として数えます。
+/// `ch`がUnicodeスカラー値として不正な場合（サロゲートや`0x10FFFF`を超える値）は0を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, Todo, count_notes_containing_char};
+///
+/// let mut app = App::default();
+/// app.todos = vec![Todo::new(1, "牛乳を買う"), Todo::new(2, "卵を買う"), Todo::new(3, "走る")].into();
+///
+/// assert_eq!(count_notes_containing_char(&app, 'を' as u32), 2);
+/// ```
+#[ffi_export]
+pub fn count_notes_containing_char(app: &App, ch: u32) -> usize {
+    let Some(ch) = char::from_u32(ch) else {
+        return 0;
+    };
+
+    app.todos
+        .iter()
+        .filter(|todo| todo.note.to_str().contains(ch))
+        .count()
+}
+
+/// 完了しているTodoの数を取得します
+///
+/// `count_done(app) + count_pending(app)`は常に`get_todo_count(app)`と等しくなります。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 完了フラグが`true`のTodoの数を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, count_done, count_pending, set_todo_done};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+/// set_todo_done(&mut app, 1, true);
+///
+/// assert_eq!(count_done(&app), 1);
+/// assert_eq!(count_pending(&app), 1);
+/// ```
+#[ffi_export]
+pub fn count_done(app: &App) -> usize {
+    app.todos.iter().filter(|todo| todo.done).count()
+}
+
+/// 完了していないTodoの数を取得します
+///
+/// `count_done(app) + count_pending(app)`は常に`get_todo_count(app)`と等しくなります。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 完了フラグが`false`のTodoの数を返します。
+#[ffi_export]
+pub fn count_pending(app: &App) -> usize {
+    app.todos.iter().filter(|todo| !todo.done).count()
+}
+
+/// 追加できるTodoの最大数を設定します
+///
+/// 上限に達している間は、`add_todo`、`add_todo_checked`、`add_todo_auto`、`add_todos`、
+/// `insert_sorted_by_id`、`append_and_consume`など、Todoを追加する関数は追加を拒否します。
+/// 複数のTodoをまとめて追加する関数は、上限に達した時点で残りを追加しません。
+/// `app_restore`は上限を超えるスナップショットの復元を拒否します。
+/// 既に上限を超えているTodoは削除されません。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `max` - Todoの最大数（0の場合は無制限）
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, set_max_todos};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// set_max_todos(&mut app, 1);
+///
+/// let note = CString::new("タスク").unwrap();
+/// assert!(add_todo(&mut app, 1, char_p::Ref::from(note.as_ref())));
+/// assert!(!add_todo(&mut app, 2, char_p::Ref::from(note.as_ref())));
+/// ```
+#[ffi_export]
+pub fn set_max_todos(app: &mut App, max: usize) {
+    app.max_todos = max;
+}
+
+/// 指定したIDのTodoの完了フラグを反転します
+///
+/// 現在の状態を読み出してから逆の値を設定する代わりに、1回の呼び出しで切り替えます。
+/// 状態は`set_todo_done`と同様に、完了にすると`Status::Done`、未完了に戻すと`Status::Todo`になります。
+/// 同じIDのTodoが複数ある場合は、最初の1件のみを更新します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの可変参照
+/// * `id` - 対象のTodoのID
+///
+/// # 戻り値
+///
+/// 反転後の完了フラグを返します。該当するTodoが存在しない場合は何も変更せずに`false`を返すため、
+/// 存在するかどうかを区別したい場合は`has_todo`と組み合わせてください。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, toggle_todo_done};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("牛乳を買う").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+///
+/// assert!(toggle_todo_done(&mut app, 1));
+/// assert!(!toggle_todo_done(&mut app, 1));
+/// ```
+#[ffi_export]
+pub fn toggle_todo_done(app: &mut App, id: i32) -> bool {
+    let Some(index) = index_of(app, id) else {
+        return false;
+    };
+
+    let todo = &mut app.todos[index];
+    todo.set_done(!todo.done);
+    todo.done
+}
+
+/// 完了しているTodoのうち、n番目（0から始まる）のインデックスを取得します
+///
+/// 完了したTodoを順に移動する場合などに利用します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+/// * `n` - 完了しているTodoの中での順番（0から始まる）
+///
+/// # 戻り値
+///
+/// 完了フラグが`true`のTodoのうち、リストの先頭から数えて`n`番目のTodoのインデックスを返します。
+/// 完了しているTodoが`n + 1`件未満の場合は`-1`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, nth_completed_index, set_todo_done};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// for id in 1..=3 {
+///     add_todo(&mut app, id, char_p::Ref::from(note.as_ref()));
+/// }
+/// set_todo_done(&mut app, 2, true);
+///
+/// assert_eq!(nth_completed_index(&app, 0), 1);
+/// assert_eq!(nth_completed_index(&app, 1), -1);
+/// ```
+#[ffi_export]
+pub fn nth_completed_index(app: &App, n: usize) -> isize {
+    app.todos
+        .iter()
+        .enumerate()
+        .filter(|(_, todo)| todo.done)
+        .nth(n)
+        .map(|(index, _)| index as isize)
+        .unwrap_or(-1) // エラー値
+}
+
+/// 最初の未完了のTodoのインデックスを取得します
+///
+/// 次に取り組むTodoに移動する場合などに利用します。
+///
+/// # 引数
+///
+/// * `app` - Todoアプリケーションインスタンスへの参照
+///
+/// # 戻り値
+///
+/// 完了フラグが`false`の最初のTodoのインデックスを返します。
+/// すべてのTodoが完了している場合やTodoが存在しない場合は`-1`を返します。
+///
+/// # 使用例
+///
+/// ```rust
+/// use safer_ffi_example::{App, add_todo, first_pending_index, set_todo_done};
+/// use safer_ffi::prelude::*;
+/// use std::ffi::CString;
+///
+/// let mut app = App::default();
+/// let note = CString::new("タスク").unwrap();
+/// add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+/// add_todo(&mut app, 2, char_p::Ref::from(note.as_ref()));
+/// set_todo_done(&mut app, 1, true);
+///
+/// assert_eq!(first_pending_index(&app), 1);
+/// ```
+#[ffi_export]
+pub fn first_pending_index(app: &App) -> isize {
+    app.todos
+        .iter()
+        .position(|todo| !todo.done)
+        .map(|index| index as isize)
+        .unwrap_or(-1) // エラー値
+}
+
+/// FFIヘッダーファイルを生成します
+///
+/// このプロジェクトのRust関数とデータ構造をC/C++/Go等から利用するための
+/// ヘッダーファイルを生成します。ビルド時に`headers`機能が有効な場合のみ利用可能です。
+///
+/// # 戻り値
+///
+/// ヘッダーファイルの生成結果を表すResult
+///
+/// # 使用例
+///
+/// ```rust,no_run
+/// #[cfg(feature = "headers")]
+/// fn main() -> std::io::Result<()> {
+///     safer_ffi_example::generate_headers()
+/// }
+///
+/// #[cfg(not(feature = "headers"))]
+/// fn main() {
+///     println!("headers機能が有効ではありません");
+/// }
+/// ```
+#[cfg(feature = "headers")]
+pub fn generate_headers() -> ::std::io::Result<()> {
+    ::safer_ffi::headers::builder()
+        .to_file("./go_example/safer_ffi_example.h")?
+        .generate()
 }
 
 #[cfg(test)]
@@ -442,126 +5625,3185 @@ mod tests {
     use super::*;
     use std::ffi::CStr;
 
-    // テスト用にchar_p::Refを作成するヘルパー関数
-    // CStringとchar_p::Refの両方を返す理由:
-    // 1. char_p::RefはCStringが持つヒープメモリ上の文字列データへのポインタを保持している
-    // 2. CStringがスコープを抜けてdropされると、そのヒープメモリは解放される
-    // 3. その後にchar_p::Refを使うと解放済みメモリ参照(use-after-free)となり、未定義動作を引き起こす
-    // 4. タプルで両方を返すことで、呼び出し側がCStringの寿命を管理でき、ダングリングポインタを防止できる
-    // 5. 呼び出し側は `let _ = cstring;` などでCStringを保持し、参照が必要な間メモリが解放されないようにする
-    //
-    // メモリ構造の図解:
-    //
-    // CString オブジェクト        ヒープ上の文字列データ
-    // +------------------+       +----------------+
-    // | ポインタ --------+-----> | 'こ', 'ん', ...|
-    // +------------------+       +----------------+
-    //                                 ↑
-    // char_p::Ref                     |
-    // +------------------+            |
-    // | ポインタ  -------+------------+
-    // +------------------+
-    //
-    // このパターンはテスト用に簡略化していますが、実際のアプリケーションではもっと体系的な
-    // 文字列ライフタイム管理方法（例：Arc<CString>など）の検討が必要かもしれません
-    fn c_str(s: &str) -> (std::ffi::CString, char_p::Ref<'_>) {
-        let cstring = std::ffi::CString::new(s).unwrap();
-        let cstr = unsafe { CStr::from_ptr(cstring.as_ptr()) };
-        let char_ref = char_p::Ref::from(cstr);
-        (cstring, char_ref) // CStringを一緒に返して、ライフタイムを延長
+    // テスト用にchar_p::Refを作成するヘルパー関数
+    // CStringとchar_p::Refの両方を返す理由:
+    // 1. char_p::RefはCStringが持つヒープメモリ上の文字列データへのポインタを保持している
+    // 2. CStringがスコープを抜けてdropされると、そのヒープメモリは解放される
+    // 3. その後にchar_p::Refを使うと解放済みメモリ参照(use-after-free)となり、未定義動作を引き起こす
+    // 4. タプルで両方を返すことで、呼び出し側がCStringの寿命を管理でき、ダングリングポインタを防止できる
+    // 5. 呼び出し側は `let _ = cstring;` などでCStringを保持し、参照が必要な間メモリが解放されないようにする
+    //
+    // メモリ構造の図解:
+    //
+    // CString オブジェクト        ヒープ上の文字列データ
+    // +------------------+       +----------------+
+    // | ポインタ --------+-----> | 'こ', 'ん', ...|
+    // +------------------+       +----------------+
+    //                                 ↑
+    // char_p::Ref                     |
+    // +------------------+            |
+    // | ポインタ  -------+------------+
+    // +------------------+
+    //
+    // このパターンはテスト用に簡略化していますが、実際のアプリケーションではもっと体系的な
+    // 文字列ライフタイム管理方法（例：Arc<CString>など）の検討が必要かもしれません
+    fn c_str(s: &str) -> (std::ffi::CString, char_p::Ref<'_>) {
+        let cstring = std::ffi::CString::new(s).unwrap();
+        let cstr = unsafe { CStr::from_ptr(cstring.as_ptr()) };
+        let char_ref = char_p::Ref::from(cstr);
+        (cstring, char_ref) // CStringを一緒に返して、ライフタイムを延長
+    }
+
+    #[test]
+    fn test_todo_new() {
+        let todo = Todo::new(42, "テストタスク");
+        assert_eq!(todo.id, 42);
+        assert_eq!(todo.note.to_str(), "テストタスク");
+    }
+
+    #[test]
+    fn test_app_new() {
+        let app = App::default();
+        assert_eq!(app.todos.len(), 0);
+    }
+
+    #[test]
+    fn test_add_todo() {
+        let mut app = App::default();
+
+        // Todoを追加
+        let (cstring1, note_ref1) = c_str("タスク1");
+        let result = add_todo(&mut app, 1, note_ref1);
+        assert!(result);
+        assert_eq!(app.todos.len(), 1);
+
+        // 2つ目のTodoを追加
+        let (cstring2, note_ref2) = c_str("タスク2");
+        add_todo(&mut app, 2, note_ref2);
+
+        assert_eq!(app.todos.len(), 2);
+        assert_eq!(app.todos[0].id, 1);
+        assert_eq!(app.todos[0].note.to_str(), "タスク1");
+        assert_eq!(app.todos[1].id, 2);
+        assert_eq!(app.todos[1].note.to_str(), "タスク2");
+
+        // CStringを変数に保持して、関数を抜けるまで生存期間を保証
+        let _ = (cstring1, cstring2);
+    }
+
+    #[test]
+    fn test_get_todo_count() {
+        let mut app = App::default();
+        assert_eq!(get_todo_count(&app), 0);
+
+        // Todoを追加
+        let (cstring, note_ref) = c_str("テスト");
+        add_todo(&mut app, 1, note_ref);
+
+        assert_eq!(get_todo_count(&app), 1);
+
+        // CStringを変数に保持
+        let _ = cstring;
+    }
+
+    #[test]
+    fn test_get_todo_id_at() {
+        let mut app = App::default();
+
+        // 範囲外のインデックスにアクセス
+        assert_eq!(get_todo_id_at(&app, 0), -1);
+
+        // Todoを追加
+        let (cstring, note_ref) = c_str("テスト");
+        add_todo(&mut app, 42, note_ref);
+
+        assert_eq!(get_todo_id_at(&app, 0), 42);
+        // 範囲外のインデックスにアクセス
+        assert_eq!(get_todo_id_at(&app, 1), -1);
+
+        // CStringを変数に保持
+        let _ = cstring;
+    }
+
+    #[test]
+    fn test_get_todo_note_at() {
+        let mut app = App::default();
+
+        // 範囲外のインデックスにアクセス
+        let empty_note = get_todo_note_at(&app, 0);
+        assert_eq!(empty_note.to_str(), "");
+
+        // Todoを追加
+        let (cstring, note_ref) = c_str("重要なタスク");
+        add_todo(&mut app, 1, note_ref);
+
+        let retrieved_note = get_todo_note_at(&app, 0);
+        assert_eq!(retrieved_note.to_str(), "重要なタスク");
+
+        // 範囲外のインデックスにアクセス
+        let empty_note2 = get_todo_note_at(&app, 1);
+        assert_eq!(empty_note2.to_str(), "");
+
+        // CStringを変数に保持
+        let _ = cstring;
+    }
+
+    // テスト用に指定した(id, note)の組からAppを組み立てるヘルパー関数
+    // add_todoを経由せずに直接Todoを並べるため、重複IDなども自由に作れる
+    fn app_with(items: &[(i32, &str)]) -> App {
+        let todos: Vec<Todo> = items
+            .iter()
+            .map(|&(id, note)| Todo::new(id, note))
+            .collect();
+        App {
+            todos: todos.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pack_all_notes() {
+        let app = app_with(&[(1, "牛乳を買う"), (2, ""), (3, "call mom")]);
+
+        let mut offsets = repr_c::Vec::from(Vec::new());
+        let mut bytes = repr_c::Vec::from(Vec::new());
+        pack_all_notes(&app, &mut offsets, &mut bytes);
+
+        // ノート数 + 番兵
+        assert_eq!(offsets.len(), app.todos.len() + 1);
+        assert_eq!(offsets[offsets.len() - 1], bytes.len());
+
+        // オフセットとバイト列から各ノートを復元できる
+        for (i, todo) in app.todos.iter().enumerate() {
+            let note = std::str::from_utf8(&bytes[offsets[i]..offsets[i + 1]]).unwrap();
+            assert_eq!(note, todo.note.to_str());
+        }
+
+        free_usize_vec(offsets);
+        free_u8_vec(bytes);
+    }
+
+    #[test]
+    fn test_pack_all_notes_empty() {
+        let app = App::default();
+
+        let mut offsets = repr_c::Vec::from(vec![42]);
+        let mut bytes = repr_c::Vec::from(vec![1, 2, 3]);
+        pack_all_notes(&app, &mut offsets, &mut bytes);
+
+        // 既存の内容は破棄され、番兵のみが残る
+        assert_eq!(&offsets[..], &[0]);
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn test_set_all_notes_packed_round_trip() {
+        let source = app_with(&[(1, "牛乳を買う"), (2, ""), (3, "call mom")]);
+        let mut offsets = repr_c::Vec::from(Vec::new());
+        let mut bytes = repr_c::Vec::from(Vec::new());
+        pack_all_notes(&source, &mut offsets, &mut bytes);
+
+        let mut app = app_with(&[(10, "x"), (20, "y"), (30, "z")]);
+        assert!(set_all_notes_packed(
+            &mut app,
+            offsets.as_ref(),
+            bytes.as_ref()
+        ));
+
+        // ノートだけが置き換わり、IDはそのまま
+        for (todo, expected) in app.todos.iter().zip(source.todos.iter()) {
+            assert_eq!(todo.note.to_str(), expected.note.to_str());
+        }
+        assert_eq!(app.todos[0].id, 10);
+        assert_eq!(app.todos[2].id, 30);
+    }
+
+    #[test]
+    fn test_set_all_notes_packed_mismatched_count() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+
+        let bytes = b"xyz";
+        let offsets = [0, 1, 2, 3];
+        assert!(!set_all_notes_packed(
+            &mut app,
+            offsets[..].into(),
+            bytes[..].into()
+        ));
+        assert_eq!(app.todos[0].note.to_str(), "a");
+        assert_eq!(app.todos[1].note.to_str(), "b");
+    }
+
+    #[test]
+    fn test_set_all_notes_packed_invalid_utf8() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+
+        // 2つ目のノートが不正なUTF-8
+        let bytes = [b'o', b'k', 0xff, 0xfe];
+        let offsets = [0, 2, 4];
+        assert!(!set_all_notes_packed(
+            &mut app,
+            offsets[..].into(),
+            bytes[..].into()
+        ));
+        assert_eq!(app.todos[0].note.to_str(), "a");
+        assert_eq!(app.todos[1].note.to_str(), "b");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_duplicate_id_pairs() {
+        let app = app_with(&[(1, "a"), (2, "b"), (1, "c"), (3, "d"), (1, "e")]);
+
+        let json = duplicate_id_pairs(&app);
+        assert_eq!(json.to_str(), r#"[{"id":1,"indices":[0,2,4]}]"#);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_duplicate_id_pairs_none() {
+        let app = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+        assert_eq!(duplicate_id_pairs(&app).to_str(), "[]");
+
+        let empty = App::default();
+        assert_eq!(duplicate_id_pairs(&empty).to_str(), "[]");
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn test_compressed_bytes_round_trip() {
+        let long_note = "牛乳を買う。".repeat(500);
+        let app = app_with(&[(1, &long_note), (2, &long_note), (-3, "short")]);
+
+        let compressed = app_to_compressed_bytes(&app);
+        // 繰り返しの多いノートは元のノートの合計より小さくなる
+        let raw_len: usize = app.todos.iter().map(|t| t.note.to_str().len()).sum();
+        assert!(compressed.len() < raw_len);
+
+        let restored = app_from_compressed_bytes(compressed.as_ref());
+        assert_eq!(restored.todos.len(), 3);
+        for (restored, original) in restored.todos.iter().zip(app.todos.iter()) {
+            assert_eq!(restored.id, original.id);
+            assert_eq!(restored.note.to_str(), original.note.to_str());
+        }
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn test_app_from_compressed_bytes_invalid() {
+        let garbage = [0xffu8, 0x00, 0x12, 0x34];
+        let app = app_from_compressed_bytes(garbage[..].into());
+        assert_eq!(app.todos.len(), 0);
+    }
+
+    // コールバックに渡されたIDをuser_data経由でVec<i32>に記録する
+    extern "C" fn record_id(id: i32, _note: char_p::Ref<'_>, user_data: *mut c_void) {
+        let ids = unsafe { &mut *(user_data as *mut Vec<i32>) };
+        ids.push(id);
+    }
+
+    #[test]
+    fn test_for_each_todo_sorted_by_id() {
+        let app = app_with(&[(3, "c"), (1, "a"), (2, "b"), (1, "a2")]);
+
+        let mut ids: Vec<i32> = Vec::new();
+        for_each_todo_sorted_by_id(&app, true, record_id, &mut ids as *mut _ as *mut c_void);
+        assert_eq!(ids, vec![1, 1, 2, 3]);
+
+        ids.clear();
+        for_each_todo_sorted_by_id(&app, false, record_id, &mut ids as *mut _ as *mut c_void);
+        assert_eq!(ids, vec![3, 2, 1, 1]);
+
+        // 保存順は変わらない
+        let raw: Vec<i32> = app.todos.iter().map(|t| t.id).collect();
+        assert_eq!(raw, vec![3, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_transaction_rollback() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        // `app`は`tx_free`までトランザクション経由でのみ扱うため、コミット・ロールバックは安全
+        let mut tx = app_begin(&mut app);
+
+        let (cstring1, note_ref1) = c_str("c");
+        add_todo(tx_app(&mut tx), 3, note_ref1);
+        let (cstring2, note_ref2) = c_str("d");
+        add_todo(tx_app(&mut tx), 4, note_ref2);
+        assert_eq!(get_todo_count(tx_app(&mut tx)), 4);
+
+        unsafe { tx_rollback(&mut tx) };
+        assert_eq!(get_todo_count(tx_app(&mut tx)), 2);
+        tx_free(tx);
+
+        // 元のアプリケーションは変更されていない
+        assert_eq!(app.todos.len(), 2);
+        assert_eq!(app.todos[0].note.to_str(), "a");
+        assert_eq!(app.todos[1].note.to_str(), "b");
+
+        let _ = (cstring1, cstring2);
+    }
+
+    #[test]
+    fn test_transaction_commit() {
+        let mut app = app_with(&[(1, "a")]);
+        // `app`は`tx_free`までトランザクション経由でのみ扱うため、コミット・ロールバックは安全
+        let mut tx = app_begin(&mut app);
+
+        let (cstring1, note_ref1) = c_str("b");
+        add_todo(tx_app(&mut tx), 2, note_ref1);
+        let (cstring2, note_ref2) = c_str("c");
+        add_todo(tx_app(&mut tx), 3, note_ref2);
+
+        unsafe { tx_commit(&mut tx) };
+        tx_free(tx);
+
+        assert_eq!(app.todos.len(), 3);
+        assert_eq!(app.todos[1].id, 2);
+        assert_eq!(app.todos[2].note.to_str(), "c");
+
+        let _ = (cstring1, cstring2);
+    }
+
+    #[test]
+    fn test_transaction_notifies_only_on_commit() {
+        use std::sync::Mutex;
+
+        // コールバックに渡されたIDを記録する
+        static CHANGED: Mutex<Vec<i32>> = Mutex::new(Vec::new());
+        extern "C" fn record(id: i32) {
+            CHANGED.lock().unwrap().push(id);
+        }
+
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        set_on_change(&mut app, Some(record));
+        // `app`は`tx_free`までトランザクション経由でのみ扱うため、コミット・ロールバックは安全
+        let mut tx = app_begin(&mut app);
+
+        // ロールバックした変更は通知されない
+        let (_note, note) = c_str("c");
+        assert!(add_todo(tx_app(&mut tx), 3, note));
+        assert!(remove_todo(tx_app(&mut tx), 1));
+        unsafe { tx_rollback(&mut tx) };
+        assert!(CHANGED.lock().unwrap().is_empty());
+
+        // コミットすると差分のIDだけが通知される
+        assert!(add_todo(tx_app(&mut tx), 4, note));
+        assert!(add_todo(tx_app(&mut tx), 5, note));
+        assert!(remove_todo(tx_app(&mut tx), 5));
+        assert!(remove_todo(tx_app(&mut tx), 2));
+        assert!(CHANGED.lock().unwrap().is_empty());
+        unsafe { tx_commit(&mut tx) };
+        assert_eq!(*CHANGED.lock().unwrap(), vec![2, 4]);
+
+        // 変更のないコミットでは通知されない
+        unsafe { tx_commit(&mut tx) };
+        tx_free(tx);
+        assert_eq!(*CHANGED.lock().unwrap(), vec![2, 4]);
+
+        // 元のアプリケーションのコールバックは残っている
+        assert!(add_todo(&mut app, 6, note));
+        assert_eq!(*CHANGED.lock().unwrap(), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn test_transaction_free_without_commit() {
+        let mut app = app_with(&[(1, "a")]);
+        let mut tx = app_begin(&mut app);
+
+        let (cstring, note_ref) = c_str("b");
+        add_todo(tx_app(&mut tx), 2, note_ref);
+        tx_free(tx);
+
+        assert_eq!(app.todos.len(), 1);
+
+        let _ = cstring;
+    }
+
+    #[test]
+    fn test_most_common_first_word() {
+        let app = app_with(&[
+            (1, "buy milk"),
+            (2, "call mom"),
+            (3, "buy eggs"),
+            (4, "  buy bread"),
+            (5, "call dad"),
+        ]);
+        assert_eq!(most_common_first_word(&app).to_str(), "buy");
+    }
+
+    #[test]
+    fn test_most_common_first_word_tie() {
+        // callとbuyが2回ずつ出現する場合は先に出現したcallを返す
+        let app = app_with(&[
+            (1, "call mom"),
+            (2, "buy milk"),
+            (3, "buy eggs"),
+            (4, "call dad"),
+            (5, "   "),
+        ]);
+        assert_eq!(most_common_first_word(&app).to_str(), "call");
+    }
+
+    #[test]
+    fn test_most_common_first_word_empty() {
+        let app = App::default();
+        assert_eq!(most_common_first_word(&app).to_str(), "");
+    }
+
+    #[test]
+    fn test_ids_in_range() {
+        let app = app_with(&[(5, "a"), (1, "b"), (3, "c"), (8, "d"), (2, "e")]);
+
+        let ids = ids_in_range(&app, 2, 5);
+        assert_eq!(&ids[..], &[5, 3, 2]);
+        free_i32_vec(ids);
+
+        // 単一の値の範囲
+        let ids = ids_in_range(&app, 8, 8);
+        assert_eq!(&ids[..], &[8]);
+        free_i32_vec(ids);
+    }
+
+    #[test]
+    fn test_ids_in_range_inverted() {
+        let app = app_with(&[(1, "a"), (2, "b")]);
+
+        let ids = ids_in_range(&app, 2, 1);
+        assert!(ids.is_empty());
+        free_i32_vec(ids);
+    }
+
+    #[test]
+    fn test_todos_equal_at() {
+        let a = app_with(&[(1, "牛乳を買う"), (2, "レポート")]);
+        let b = app_with(&[(2, "レポート"), (1, "牛乳を買う")]);
+
+        assert!(todos_equal_at(&a, 0, &b, 1));
+        assert!(todos_equal_at(&a, 1, &b, 0));
+    }
+
+    #[test]
+    fn test_todos_equal_at_different() {
+        let a = app_with(&[(1, "牛乳を買う")]);
+        let b = app_with(&[(1, "卵を買う"), (2, "牛乳を買う")]);
+
+        // 同じIDでもノートが異なる
+        assert!(!todos_equal_at(&a, 0, &b, 0));
+        // 同じノートでもIDが異なる
+        assert!(!todos_equal_at(&a, 0, &b, 1));
+    }
+
+    #[test]
+    fn test_todos_equal_at_out_of_range() {
+        let a = app_with(&[(1, "a")]);
+        let b = a.clone();
+
+        assert!(!todos_equal_at(&a, 1, &b, 0));
+        assert!(!todos_equal_at(&a, 0, &b, 1));
+    }
+
+    #[test]
+    fn test_app_from_tsv() {
+        let (cstring, text) = c_str("1\t牛乳を買う\n2\tレポートを書く\n-3\ttab\tinside");
+        let app = app_from_tsv(text);
+
+        assert_eq!(app.todos.len(), 3);
+        assert_eq!(app.todos[0].id, 1);
+        assert_eq!(app.todos[0].note.to_str(), "牛乳を買う");
+        assert_eq!(app.todos[1].id, 2);
+        assert_eq!(app.todos[1].note.to_str(), "レポートを書く");
+        // 最初のタブ以降はすべてノートとして扱う
+        assert_eq!(app.todos[2].id, -3);
+        assert_eq!(app.todos[2].note.to_str(), "tab\tinside");
+
+        app_free(app);
+        let _ = cstring;
+    }
+
+    #[test]
+    fn test_app_from_tsv_skips_bad_rows() {
+        let (cstring, text) = c_str("1\tok\nabc\tbad id\nno tab\n\n2\talso ok");
+        let app = app_from_tsv(text);
+
+        let ids: Vec<i32> = app.todos.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        let _ = cstring;
+    }
+
+    #[test]
+    fn test_app_from_tsv_crlf() {
+        let (cstring, text) = c_str("1\t牛乳\r\n2\t卵\r\n");
+        let app = app_from_tsv(text);
+
+        assert_eq!(app.todos.len(), 2);
+        assert_eq!(app.todos[0].note.to_str(), "牛乳");
+        assert_eq!(app.todos[1].note.to_str(), "卵");
+
+        let _ = cstring;
+    }
+
+    #[test]
+    fn test_indices_over_byte_limit() {
+        let app = app_with(&[(1, "1234"), (2, "12345"), (3, "123456"), (4, "")]);
+
+        // ちょうど上限のノートは含まれない
+        let indices = indices_over_byte_limit(&app, 5);
+        assert_eq!(&indices[..], &[2]);
+        free_usize_vec(indices);
+    }
+
+    #[test]
+    fn test_indices_over_byte_limit_multibyte() {
+        // "牛乳"は2文字だがUTF-8では6バイト
+        let app = app_with(&[(1, "牛乳"), (2, "milk")]);
+
+        let indices = indices_over_byte_limit(&app, 4);
+        assert_eq!(&indices[..], &[0]);
+        free_usize_vec(indices);
+
+        let indices = indices_over_byte_limit(&app, 6);
+        assert!(indices.is_empty());
+        free_usize_vec(indices);
+    }
+
+    #[test]
+    fn test_free_string() {
+        // 計数による解放の確認は`leak-check`フィーチャーでtests/leak_check.rsが行う
+        let app = app_with(&[(1, "牛乳を買う")]);
+
+        let note = get_todo_note_at(&app, 0);
+        assert_eq!(note.to_str(), "牛乳を買う");
+        free_string(note);
+
+        // 範囲外で返される空文字列も同様に解放できる
+        free_string(get_todo_note_at(&app, 1));
+        free_string(most_common_first_word(&app));
+
+        // 元のノートは影響を受けない
+        assert_eq!(app.todos[0].note.to_str(), "牛乳を買う");
+    }
+
+    #[test]
+    fn test_string_buf_reuse() {
+        let app = app_with(&[(1, "短い"), (2, "ずっと長いノートの内容です"), (3, "x")]);
+        let mut buf = string_buf_new();
+        assert_eq!(string_buf_ptr(&buf).to_str(), "");
+
+        // 同じバッファで複数回読み込み、毎回内容が更新される
+        for (index, todo) in app.todos.iter().enumerate() {
+            assert!(get_todo_note_into(&app, index, &mut buf));
+            assert_eq!(string_buf_ptr(&buf).to_str(), todo.note.to_str());
+        }
+
+        // 範囲外の場合は失敗し、内容はそのまま
+        assert!(!get_todo_note_into(&app, 3, &mut buf));
+        assert_eq!(string_buf_ptr(&buf).to_str(), "x");
+
+        string_buf_free(buf);
+    }
+
+    #[test]
+    fn test_chunk_app() {
+        let app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")]);
+
+        // 最初のチャンク
+        let first = chunk_app(&app, 2, 0);
+        let ids: Vec<i32> = first.todos.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(first.todos[1].note.to_str(), "b");
+        app_free(first);
+
+        // 端数の最後のチャンク
+        let last = chunk_app(&app, 2, 2);
+        let ids: Vec<i32> = last.todos.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![5]);
+        app_free(last);
+
+        // 元のアプリケーションは変更されない
+        assert_eq!(app.todos.len(), 5);
+    }
+
+    #[test]
+    fn test_chunk_app_out_of_range() {
+        let app = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        assert_eq!(chunk_app(&app, 2, 2).todos.len(), 0);
+        assert_eq!(chunk_app(&app, 0, 0).todos.len(), 0);
+        assert_eq!(chunk_app(&App::default(), 2, 0).todos.len(), 0);
+    }
+
+    #[test]
+    fn test_id_of_longest_note() {
+        let app = app_with(&[(1, "ab"), (2, "abcd"), (3, "abc")]);
+        assert_eq!(id_of_longest_note(&app), 2);
+
+        // バイト数で比較する（"牛乳"は6バイト）
+        let app = app_with(&[(1, "abcde"), (2, "牛乳")]);
+        assert_eq!(id_of_longest_note(&app), 2);
+    }
+
+    #[test]
+    fn test_id_of_longest_note_tie() {
+        let app = app_with(&[(1, "a"), (2, "abc"), (3, "xyz")]);
+        assert_eq!(id_of_longest_note(&app), 2);
+    }
+
+    #[test]
+    fn test_id_of_longest_note_empty() {
+        assert_eq!(id_of_longest_note(&App::default()), -1);
+    }
+
+    #[test]
+    fn test_add_log_entry() {
+        let mut app = App::default();
+
+        let (cstring1, message1) = c_str("サーバーを起動");
+        assert!(add_log_entry(&mut app, 1, message1, 1700000000));
+        let (cstring2, message2) = c_str("negative");
+        assert!(add_log_entry(&mut app, 2, message2, -5));
+
+        assert_eq!(app.todos.len(), 2);
+        assert_eq!(app.todos[0].id, 1);
+        assert_eq!(app.todos[0].note.to_str(), "[1700000000] サーバーを起動");
+        assert_eq!(app.todos[1].note.to_str(), "[-5] negative");
+        // 同じIDは追加できない
+        assert!(!add_log_entry(&mut app, 1, message2, 0));
+
+        let _ = (cstring1, cstring2);
+    }
+
+    #[test]
+    fn test_add_log_entry_stops_at_nul() {
+        // char_p::Refは最初のNULで終わるため、メッセージの途中にNULがあっても
+        // それ以降はノートに含まれず、NULを含むノートが保存されることはない
+        let mut app = App::default();
+        let message_bytes = b"mes\0sage\0";
+        let message = char_p::Ref::from(CStr::from_bytes_until_nul(message_bytes).unwrap());
+
+        assert!(add_log_entry(&mut app, 1, message, 42));
+        assert_eq!(app.note_at(0), Some("[42] mes".to_string()));
+        assert!(!app.todos[0].note.to_bytes().contains(&0));
+    }
+
+    #[test]
+    fn test_distinct_note_count() {
+        let unique = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+        assert_eq!(distinct_note_count(&unique), 3);
+
+        let duplicated = app_with(&[(1, "a"), (2, "b"), (3, "a"), (4, "a"), (5, "")]);
+        assert_eq!(distinct_note_count(&duplicated), 3);
+
+        assert_eq!(distinct_note_count(&App::default()), 0);
+    }
+
+    // 走査中に元のアプリケーションへTodoを追加するコールバック
+    struct GrowingVisit {
+        app: *mut App,
+        visited: Vec<(i32, String)>,
+    }
+
+    extern "C" fn visit_and_grow(id: i32, note: char_p::Ref<'_>, user_data: *mut c_void) {
+        let visit = unsafe { &mut *(user_data as *mut GrowingVisit) };
+        visit.visited.push((id, note.to_str().to_string()));
+
+        let (cstring, new_note) = c_str("追加");
+        add_todo(unsafe { &mut *visit.app }, id + 100, new_note);
+        let _ = cstring;
+    }
+
+    #[test]
+    fn test_for_each_todo_snapshot_tolerates_mutation() {
+        let app: *mut App = Box::into_raw(Box::new(app_with(&[(1, "a"), (2, "b"), (3, "c")])));
+        let mut visit = GrowingVisit {
+            app,
+            visited: Vec::new(),
+        };
+
+        // 共有参照を作らずにポインタのまま渡し、コールバックだけが`&mut App`を作る
+        unsafe {
+            for_each_todo_snapshot(app, visit_and_grow, &mut visit as *mut _ as *mut c_void);
+        }
+
+        // 走査したのは元の3件のみ
+        assert_eq!(
+            visit.visited,
+            vec![
+                (1, "a".to_string()),
+                (2, "b".to_string()),
+                (3, "c".to_string())
+            ]
+        );
+        // コールバックによる追加は反映されている
+        let app = unsafe { Box::from_raw(app) };
+        assert_eq!(app.todos.len(), 6);
+    }
+
+    fn ids_of(app: &App) -> Vec<i32> {
+        app.todos.iter().map(|todo| todo.id).collect()
+    }
+
+    #[test]
+    fn test_reverse_range() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")]);
+
+        // 中間の範囲
+        assert!(reverse_range(&mut app, 1, 4));
+        assert_eq!(ids_of(&app), vec![1, 4, 3, 2, 5]);
+        assert_eq!(app.todos[1].note.to_str(), "d");
+
+        // 全体
+        assert!(reverse_range(&mut app, 0, 5));
+        assert_eq!(ids_of(&app), vec![5, 2, 3, 4, 1]);
+
+        // 1要素や空の範囲は何も変わらない
+        assert!(reverse_range(&mut app, 2, 3));
+        assert!(reverse_range(&mut app, 5, 5));
+        assert_eq!(ids_of(&app), vec![5, 2, 3, 4, 1]);
+    }
+
+    #[test]
+    fn test_reverse_range_invalid() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        assert!(!reverse_range(&mut app, 2, 1));
+        assert!(!reverse_range(&mut app, 0, 4));
+        assert_eq!(ids_of(&app), vec![1, 2, 3]);
+    }
+
+    // 呼び出し回数をuser_data経由で数え、IDが正かどうかを判定する
+    extern "C" fn count_and_check_positive(
+        id: i32,
+        _note: char_p::Ref<'_>,
+        user_data: *mut c_void,
+    ) -> bool {
+        let calls = unsafe { &mut *(user_data as *mut usize) };
+        *calls += 1;
+        id > 0
+    }
+
+    #[test]
+    fn test_any_todo_matches() {
+        let app = app_with(&[(-1, "a"), (2, "b"), (3, "c"), (-4, "d")]);
+
+        let mut calls = 0usize;
+        assert!(any_todo_matches(
+            &app,
+            count_and_check_positive,
+            &mut calls as *mut _ as *mut c_void
+        ));
+        // 2件目で見つかった時点で終了する
+        assert_eq!(calls, 2);
+
+        let none = app_with(&[(-1, "a"), (-2, "b")]);
+        calls = 0;
+        assert!(!any_todo_matches(
+            &none,
+            count_and_check_positive,
+            &mut calls as *mut _ as *mut c_void
+        ));
+        assert_eq!(calls, 2);
+
+        assert!(!any_todo_matches(
+            &App::default(),
+            count_and_check_positive,
+            &mut calls as *mut _ as *mut c_void
+        ));
+    }
+
+    #[test]
+    fn test_all_todos_match() {
+        let app = app_with(&[(1, "a"), (-2, "b"), (3, "c"), (4, "d")]);
+
+        let mut calls = 0usize;
+        assert!(!all_todos_match(
+            &app,
+            count_and_check_positive,
+            &mut calls as *mut _ as *mut c_void
+        ));
+        // 2件目で条件を満たさないTodoが見つかった時点で終了する
+        assert_eq!(calls, 2);
+
+        let all = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+        calls = 0;
+        assert!(all_todos_match(
+            &all,
+            count_and_check_positive,
+            &mut calls as *mut _ as *mut c_void
+        ));
+        assert_eq!(calls, 3);
+
+        // 空の場合は真
+        calls = 0;
+        assert!(all_todos_match(
+            &App::default(),
+            count_and_check_positive,
+            &mut calls as *mut _ as *mut c_void
+        ));
+        assert_eq!(calls, 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_ids_to_json() {
+        assert_eq!(ids_to_json(&App::default()).to_str(), "[]");
+
+        let app = app_with(&[(3, "秘密のメモ"), (-1, "note"), (42, "\"quoted\"")]);
+        let json = ids_to_json(&app);
+        assert_eq!(json.to_str(), "[3,-1,42]");
+
+        // ノートの内容は含まれない
+        assert!(!json.to_str().contains("秘密"));
+        assert!(!json.to_str().contains("note"));
+    }
+
+    // 「済:」で始まるノートを完了済みとみなす
+    extern "C" fn is_finished(_id: i32, note: char_p::Ref<'_>, _user_data: *mut c_void) -> bool {
+        note.to_str().starts_with("済:")
+    }
+
+    #[test]
+    fn test_drain_matching() {
+        let mut app = app_with(&[
+            (1, "済:牛乳を買う"),
+            (2, "レポートを書く"),
+            (3, "済:電話する"),
+            (4, "掃除する"),
+            (5, "済:洗濯する"),
+        ]);
+
+        let drained = drain_matching(&mut app, is_finished, std::ptr::null_mut());
+
+        // 取り出したTodoは元の順番を保つ
+        assert_eq!(ids_of(&drained), vec![1, 3, 5]);
+        assert_eq!(drained.todos[1].note.to_str(), "済:電話する");
+        // 元のアプリケーションには残りのTodoが順番どおり残る
+        assert_eq!(ids_of(&app), vec![2, 4]);
+        assert_eq!(app.todos[0].note.to_str(), "レポートを書く");
+
+        app_free(drained);
+    }
+
+    #[test]
+    fn test_drain_matching_none() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+
+        let drained = drain_matching(&mut app, is_finished, std::ptr::null_mut());
+        assert_eq!(drained.todos.len(), 0);
+        assert_eq!(ids_of(&app), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_common_note_prefix() {
+        let app = app_with(&[(1, "買い物: 牛乳"), (2, "買い物: 卵"), (3, "買い物")]);
+        assert_eq!(common_note_prefix(&app).to_str(), "買い物");
+
+        // 同じ先頭バイトを持つ異なる文字（"牛"と"特"）の途中で途切れない
+        let app = app_with(&[(1, "牛乳"), (2, "特売")]);
+        assert_eq!(common_note_prefix(&app).to_str(), "");
+    }
+
+    #[test]
+    fn test_common_note_prefix_no_common() {
+        let app = app_with(&[(1, "apple"), (2, "banana")]);
+        assert_eq!(common_note_prefix(&app).to_str(), "");
+    }
+
+    #[test]
+    fn test_common_note_prefix_single_and_empty() {
+        let app = app_with(&[(1, "牛乳を買う")]);
+        assert_eq!(common_note_prefix(&app).to_str(), "牛乳を買う");
+
+        assert_eq!(common_note_prefix(&App::default()).to_str(), "");
+    }
+
+    #[test]
+    fn test_cycle_status_at() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        let mut status = Status::Done;
+        assert!(get_status_at(&app, 0, &mut status));
+        assert_eq!(status, Status::Todo);
+
+        // Todo → Doing → Done → Todo と循環する
+        for expected in [Status::Doing, Status::Done, Status::Todo] {
+            assert!(cycle_status_at(&mut app, 0));
+            assert!(get_status_at(&app, 0, &mut status));
+            assert_eq!(status, expected);
+        }
+
+        // 他のTodoは影響を受けない
+        assert_eq!(app.todos[1].status, Status::Todo);
+    }
+
+    #[test]
+    fn test_set_status_at() {
+        let mut app = app_with(&[(1, "a")]);
+
+        assert!(set_status_at(&mut app, 0, Status::Done));
+        assert_eq!(app.todos[0].status, Status::Done);
+        assert!(cycle_status_at(&mut app, 0));
+        assert_eq!(app.todos[0].status, Status::Todo);
+    }
+
+    #[test]
+    fn test_status_out_of_range() {
+        let mut app = app_with(&[(1, "a")]);
+
+        assert!(!cycle_status_at(&mut app, 1));
+        assert!(!set_status_at(&mut app, 1, Status::Done));
+
+        let mut status = Status::Doing;
+        assert!(!get_status_at(&app, 1, &mut status));
+        assert_eq!(status, Status::Doing);
+    }
+
+    #[test]
+    fn test_estimated_json_size_grows_with_escapes() {
+        assert!(estimated_json_size(&App::default()) >= "[]".len());
+
+        // エスケープが必要な文字は多めに見積もられる
+        let plain = app_with(&[(1, "ab")]);
+        let escaped = app_with(&[(1, "\"\\")]);
+        assert!(estimated_json_size(&escaped) > estimated_json_size(&plain));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_estimated_json_size_is_upper_bound() {
+        let long_note = "牛乳を買う".repeat(100);
+        let apps = [
+            App::default(),
+            app_with(&[(1, "牛乳を買う")]),
+            app_with(&[(i32::MIN, "a"), (i32::MAX, &long_note), (-1, "")]),
+            app_with(&[
+                (1, "\"quoted\" and \\backslash\\"),
+                (2, "line1\nline2\r\n\ttab"),
+                (3, "\u{1}\u{2}\u{1f}\u{7f}"),
+            ]),
+        ];
+
+        for mut app in apps {
+            for todo in app.todos.iter_mut() {
+                todo.set_status(Status::Doing);
+            }
+            if let Some(todo) = app.todos.get_mut(0) {
+                todo.category = "\"分類\"\n".to_string().try_into().unwrap();
+            }
+            if let Some(todo) = app.todos.last_mut() {
+                todo.priority = Priority::High;
+            }
+            let actual = app_to_json(&app).to_str().len();
+            assert!(estimated_json_size(&app) >= actual);
+        }
+    }
+
+    #[test]
+    fn test_set_ids_to_positions() {
+        let mut app = app_with(&[(42, "a"), (-7, "b"), (42, "c")]);
+
+        set_ids_to_positions(&mut app, 0);
+        assert_eq!(ids_of(&app), vec![0, 1, 2]);
+
+        set_ids_to_positions(&mut app, 100);
+        assert_eq!(ids_of(&app), vec![100, 101, 102]);
+        // ノートは変わらない
+        assert_eq!(app.todos[1].note.to_str(), "b");
+    }
+
+    #[test]
+    fn test_set_ids_to_positions_saturates() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        set_ids_to_positions(&mut app, i32::MAX - 1);
+        assert_eq!(ids_of(&app), vec![i32::MAX - 1, i32::MAX, i32::MAX]);
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        // マルチバイト文字も1文字として数える
+        assert_eq!(levenshtein("牛乳を買う", "牛乳を飼う"), 1);
+    }
+
+    #[test]
+    fn test_find_closest_note() {
+        let app = app_with(&[(1, "buy milk"), (2, "call mom"), (3, "buy mill")]);
+
+        // 完全一致
+        let (cstring1, exact) = c_str("call mom");
+        assert_eq!(find_closest_note(&app, exact, 0), 1);
+
+        // 1文字の入力ミス（"buy milk"と"buy mill"はどちらも距離1なので先の方）
+        let (cstring2, typo) = c_str("buy milx");
+        assert_eq!(find_closest_note(&app, typo, 1), 0);
+        assert_eq!(find_closest_note(&app, typo, 0), -1);
+
+        let _ = (cstring1, cstring2);
+    }
+
+    #[test]
+    fn test_find_closest_note_too_distant() {
+        let app = app_with(&[(1, "buy milk")]);
+
+        let (cstring, query) = c_str("write report");
+        assert_eq!(find_closest_note(&app, query, 3), -1);
+        assert_eq!(find_closest_note(&App::default(), query, 100), -1);
+
+        let _ = cstring;
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_todo_to_json_at() {
+        let mut app = app_with(&[(1, "牛乳を買う"), (-2, "say \"hi\"")]);
+        set_status_at(&mut app, 1, Status::Done);
+
+        assert_eq!(
+            todo_to_json_at(&app, 0).to_str(),
+            r#"{"id":1,"note":"牛乳を買う","status":"todo","done":false}"#
+        );
+        assert_eq!(
+            todo_to_json_at(&app, 1).to_str(),
+            r#"{"id":-2,"note":"say \"hi\"","status":"done","done":true}"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_todo_to_json_at_out_of_range() {
+        let app = app_with(&[(1, "a")]);
+        assert_eq!(todo_to_json_at(&app, 1).to_str(), "");
+        assert_eq!(todo_to_json_at(&App::default(), 0).to_str(), "");
+    }
+
+    #[test]
+    fn test_insert_sorted_by_id() {
+        let mut app = app_with(&[(10, "a"), (20, "b"), (30, "c")]);
+        let (cstring, note) = c_str("new");
+
+        // 中間
+        assert_eq!(insert_sorted_by_id(&mut app, 25, note), 2);
+        assert_eq!(ids_of(&app), vec![10, 20, 25, 30]);
+        assert_eq!(app.todos[2].note.to_str(), "new");
+
+        // 先頭
+        assert_eq!(insert_sorted_by_id(&mut app, 5, note), 0);
+        // 末尾
+        assert_eq!(insert_sorted_by_id(&mut app, 40, note), 5);
+        // 同じIDは挿入しない
+        assert_eq!(insert_sorted_by_id(&mut app, 20, note), -1);
+        assert_eq!(ids_of(&app), vec![5, 10, 20, 25, 30, 40]);
+        assert_eq!(app.todos[2].note.to_str(), "b");
+
+        let _ = cstring;
+    }
+
+    #[test]
+    fn test_insert_sorted_by_id_empty() {
+        let mut app = App::default();
+        let (cstring, note) = c_str("first");
+
+        assert_eq!(insert_sorted_by_id(&mut app, 7, note), 0);
+        assert_eq!(ids_of(&app), vec![7]);
+
+        let _ = cstring;
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_to_canonical_json() {
+        let a = app_with(&[(3, "c"), (1, "a"), (2, "b2"), (2, "b1")]);
+        let b = app_with(&[(2, "b1"), (1, "a"), (3, "c"), (2, "b2")]);
+
+        let json_a = app_to_canonical_json(&a);
+        let json_b = app_to_canonical_json(&b);
+        assert_eq!(json_a.to_str().as_bytes(), json_b.to_str().as_bytes());
+        assert_eq!(
+            json_a.to_str(),
+            concat!(
+                r#"[{"id":1,"note":"a","status":"todo","done":false},"#,
+                r#"{"id":2,"note":"b1","status":"todo","done":false},"#,
+                r#"{"id":2,"note":"b2","status":"todo","done":false},"#,
+                r#"{"id":3,"note":"c","status":"todo","done":false}]"#
+            )
+        );
+
+        // 元の並び順は変わらない
+        assert_eq!(ids_of(&a), vec![3, 1, 2, 2]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_to_canonical_json_compares_every_field() {
+        // IDとノートと状態が同じで、分類と優先度だけが異なるTodoを逆の順番で保持する
+        let mut a = app_with(&[(1, "a"), (1, "a"), (1, "a")]);
+        a.todos[0].category = "仕事".to_string().try_into().unwrap();
+        a.todos[1].priority = Priority::High;
+        let mut b = app_with(&[(1, "a"), (1, "a"), (1, "a")]);
+        b.todos[2].category = "仕事".to_string().try_into().unwrap();
+        b.todos[1].priority = Priority::High;
+
+        assert_eq!(
+            app_to_canonical_json(&a).to_str().as_bytes(),
+            app_to_canonical_json(&b).to_str().as_bytes()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_to_canonical_json_empty() {
+        assert_eq!(app_to_canonical_json(&App::default()).to_str(), "[]");
+    }
+
+    #[test]
+    fn test_blank_note_count() {
+        let app = app_with(&[
+            (1, ""),
+            (2, "   "),
+            (3, "\t\n"),
+            (4, "　"),
+            (5, "牛乳を買う"),
+            (6, " 前後に空白 "),
+        ]);
+        assert_eq!(blank_note_count(&app), 4);
+
+        let filled = app_with(&[(1, "a"), (2, "b")]);
+        assert_eq!(blank_note_count(&filled), 0);
+
+        assert_eq!(blank_note_count(&App::default()), 0);
+    }
+
+    #[test]
+    fn test_ids_with_repeated_notes() {
+        let app = app_with(&[(1, "a"), (2, "b"), (3, "a"), (4, "c"), (5, "a")]);
+        assert_eq!(&ids_with_repeated_notes(&app)[..], &[1, 3, 5]);
+
+        let unique = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+        assert!(ids_with_repeated_notes(&unique).is_empty());
+
+        let identical = app_with(&[(7, "x"), (8, "x"), (9, "x")]);
+        assert_eq!(&ids_with_repeated_notes(&identical)[..], &[7, 8, 9]);
+
+        assert!(ids_with_repeated_notes(&App::default()).is_empty());
+    }
+
+    #[test]
+    fn test_swap_notes() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+        set_status_at(&mut app, 0, Status::Done);
+
+        assert!(swap_notes(&mut app, 2, 0));
+        assert_eq!(ids_of(&app), vec![1, 2, 3]);
+        assert_eq!(app.todos[0].note.to_str(), "c");
+        assert_eq!(app.todos[2].note.to_str(), "a");
+        assert_eq!(app.todos[0].status, Status::Done);
+        assert_eq!(app.todos[2].status, Status::Todo);
+        assert_eq!(app.todos[1].note.to_str(), "b");
+    }
+
+    #[test]
+    fn test_swap_notes_self_and_out_of_range() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+
+        assert!(swap_notes(&mut app, 1, 1));
+        assert_eq!(app.todos[1].note.to_str(), "b");
+
+        assert!(!swap_notes(&mut app, 0, 2));
+        assert!(!swap_notes(&mut app, 5, 0));
+        assert_eq!(app.todos[0].note.to_str(), "a");
+        assert_eq!(app.todos[1].note.to_str(), "b");
+    }
+
+    #[test]
+    fn test_export_todo_c_array() {
+        let app = app_with(&[(1, "牛乳を買う"), (2, ""), (3, "電話")]);
+
+        let mut ptr = std::ptr::null();
+        let mut len = 0;
+        let handle = export_todo_c_array(&app, &mut ptr, &mut len);
+        assert_eq!(len, 3);
+
+        let todos = unsafe { std::slice::from_raw_parts(ptr, len) };
+        let read: Vec<(i32, &str)> = todos
+            .iter()
+            .map(|todo| {
+                let bytes = unsafe { std::slice::from_raw_parts(todo.note_ptr, todo.note_len) };
+                (todo.id, std::str::from_utf8(bytes).unwrap())
+            })
+            .collect();
+        assert_eq!(read, vec![(1, "牛乳を買う"), (2, ""), (3, "電話")]);
+
+        free_todo_c_array(handle);
+    }
+
+    #[test]
+    fn test_export_todo_c_array_empty() {
+        let mut ptr = std::ptr::null();
+        let mut len = usize::MAX;
+        let handle = export_todo_c_array(&App::default(), &mut ptr, &mut len);
+        assert_eq!(len, 0);
+        free_todo_c_array(handle);
+    }
+
+    #[test]
+    fn test_search_any() {
+        let app = app_with(&[
+            (1, "牛乳を買う"),
+            (2, "レポートを書く"),
+            (3, "卵と牛乳を買う"),
+            (4, "電話する"),
+        ]);
+        let (_milk, milk) = c_str("牛乳");
+        let (_egg, egg) = c_str("卵");
+        let (_phone, phone) = c_str("電話");
+
+        let needles = [milk, phone];
+        assert_eq!(&search_any(&app, needles[..].into())[..], &[0, 2, 3]);
+
+        // 複数のキーワードに一致しても1回だけ
+        let overlapping = [milk, egg];
+        assert_eq!(&search_any(&app, overlapping[..].into())[..], &[0, 2]);
+    }
+
+    #[test]
+    fn test_search_any_no_needles() {
+        let app = app_with(&[(1, "a"), (2, "b")]);
+        let needles: [char_p::Ref<'_>; 0] = [];
+        assert!(search_any(&app, needles[..].into()).is_empty());
+    }
+
+    #[test]
+    fn test_set_note_from_bytes_at() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+
+        assert!(set_note_from_bytes_at(
+            &mut app,
+            1,
+            "卵を買う".as_bytes().into()
+        ));
+        assert_eq!(app.todos[1].note.to_str(), "卵を買う");
+        assert_eq!(app.todos[1].id, 2);
+
+        assert!(set_note_from_bytes_at(&mut app, 0, b"".as_slice().into()));
+        assert_eq!(app.todos[0].note.to_str(), "");
+    }
+
+    #[test]
+    fn test_set_note_from_bytes_at_rejects_invalid_input() {
+        let mut app = app_with(&[(1, "a")]);
+
+        // 不正なUTF-8
+        assert!(!set_note_from_bytes_at(
+            &mut app,
+            0,
+            b"\xff\xfe".as_slice().into()
+        ));
+        // NULを含む
+        assert!(!set_note_from_bytes_at(
+            &mut app,
+            0,
+            b"ab\0cd".as_slice().into()
+        ));
+        // 範囲外
+        assert!(!set_note_from_bytes_at(
+            &mut app,
+            1,
+            b"ok".as_slice().into()
+        ));
+
+        assert_eq!(app.todos[0].note.to_str(), "a");
+    }
+
+    #[test]
+    fn test_note_length_histogram() {
+        let app = app_with(&[
+            (1, ""),
+            (2, "abc"),
+            (3, "abcd"),
+            (4, "abcdefghijk"),
+            (5, "ab"),
+        ]);
+
+        // [0,4): "", "abc", "ab" / [4,8): "abcd" / [8,12): "abcdefghijk"
+        assert_eq!(&note_length_histogram(&app, 4)[..], &[3, 1, 1]);
+        // [0,5): 4件 / [5,10): 0件 / [10,15): 1件
+        assert_eq!(&note_length_histogram(&app, 5)[..], &[4, 0, 1]);
+        assert_eq!(&note_length_histogram(&app, 100)[..], &[5]);
+    }
+
+    #[test]
+    fn test_note_length_histogram_edge_cases() {
+        let app = app_with(&[(1, "abc")]);
+        assert!(note_length_histogram(&app, 0).is_empty());
+        assert!(note_length_histogram(&App::default(), 4).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_update_todo_from_json_at() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        app.todos[1].status = Status::Doing;
+
+        let (_json, json) = c_str(r#"{"id":20,"note":"更新後"}"#);
+        assert!(update_todo_from_json_at(&mut app, 1, json));
+        assert_eq!(app.todos[1].id, 20);
+        assert_eq!(app.todos[1].note.to_str(), "更新後");
+        assert_eq!(app.todos[1].status, Status::Todo);
+        assert_eq!(app.todos[0].note.to_str(), "a");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_update_todo_from_json_at_rejects_invalid_input() {
+        let mut app = app_with(&[(1, "a")]);
+        let before = app.todos[0].clone();
+
+        let (_malformed, malformed) = c_str(r#"{"id":2,"note":"#);
+        assert!(!update_todo_from_json_at(&mut app, 0, malformed));
+
+        let (_nul, nul) = c_str(r#"{"id":2,"note":"a\u0000b"}"#);
+        assert!(!update_todo_from_json_at(&mut app, 0, nul));
+
+        let (_valid, valid) = c_str(r#"{"id":2,"note":"b"}"#);
+        assert!(!update_todo_from_json_at(&mut app, 1, valid));
+
+        assert_eq!(app.todos[0], before);
+    }
+
+    #[test]
+    fn test_missing_ids() {
+        let app = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        assert!(missing_ids(&app, [3, 1, 2][..].into()).is_empty());
+        assert_eq!(&missing_ids(&app, [5, 1, 4, 5, 2][..].into())[..], &[5, 4]);
+        assert!(missing_ids(&app, [][..].into()).is_empty());
+        assert_eq!(
+            &missing_ids(&App::default(), [7, 8][..].into())[..],
+            &[7, 8]
+        );
+    }
+
+    #[test]
+    fn test_extra_ids() {
+        let app = app_with(&[(4, "a"), (1, "b"), (2, "c"), (4, "d")]);
+
+        assert_eq!(&extra_ids(&app, [1, 2][..].into())[..], &[4]);
+        assert!(extra_ids(&app, [1, 2, 4, 9][..].into()).is_empty());
+        assert_eq!(&extra_ids(&app, [][..].into())[..], &[4, 1, 2]);
+        assert!(extra_ids(&App::default(), [1][..].into()).is_empty());
+    }
+
+    #[test]
+    fn test_append_and_consume() {
+        let mut dst = app_with(&[(1, "a"), (2, "b")]);
+        let src = app_with(&[(3, "c"), (4, "d"), (5, "e")]);
+        let src_note_ptrs: Vec<*const u8> = src
+            .todos
+            .iter()
+            .map(|todo| todo.note.to_str().as_ptr())
+            .collect();
+
+        assert_eq!(append_and_consume(&mut dst, Box::new(src).into()), 3);
+
+        assert_eq!(ids_of(&dst), vec![1, 2, 3, 4, 5]);
+        assert_eq!(dst.todos[4].note.to_str(), "e");
+
+        // ノートは複製されず、元の確保領域がそのまま使われる
+        let moved_ptrs: Vec<*const u8> = dst.todos[2..]
+            .iter()
+            .map(|todo| todo.note.to_str().as_ptr())
+            .collect();
+        assert_eq!(moved_ptrs, src_note_ptrs);
+    }
+
+    #[test]
+    fn test_median_note_byte_len() {
+        let odd = app_with(&[(1, "abcde"), (2, ""), (3, "ab")]);
+        assert!((median_note_byte_len(&odd) - 2.0).abs() < f64::EPSILON);
+
+        let even = app_with(&[(1, "abcd"), (2, "a"), (3, "abcdefgh"), (4, "ab")]);
+        assert!((median_note_byte_len(&even) - 3.0).abs() < f64::EPSILON);
+
+        // マルチバイト文字はバイト数で数える
+        let multibyte = app_with(&[(1, "あ"), (2, "ab")]);
+        assert!((median_note_byte_len(&multibyte) - 2.5).abs() < f64::EPSILON);
+
+        assert_eq!(median_note_byte_len(&App::default()), 0.0);
+    }
+
+    #[test]
+    fn test_set_and_get_category_at() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        assert_eq!(get_category_at(&app, 0).to_str(), "");
+
+        let (_category, category) = c_str("買い物");
+        assert!(set_category_at(&mut app, 1, category));
+        assert_eq!(get_category_at(&app, 1).to_str(), "買い物");
+        assert_eq!(get_category_at(&app, 0).to_str(), "");
+
+        assert!(!set_category_at(&mut app, 2, category));
+        assert_eq!(get_category_at(&app, 2).to_str(), "");
+    }
+
+    #[test]
+    fn test_ids_in_category() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d")]);
+        let (_work, work) = c_str("仕事");
+        let (_home, home) = c_str("家");
+        set_category_at(&mut app, 0, work);
+        set_category_at(&mut app, 1, home);
+        set_category_at(&mut app, 3, work);
+
+        assert_eq!(&ids_in_category(&app, work)[..], &[1, 4]);
+        assert_eq!(&ids_in_category(&app, home)[..], &[2]);
+
+        let (_empty, empty) = c_str("");
+        assert_eq!(&ids_in_category(&app, empty)[..], &[3]);
+
+        let (_unused, unused) = c_str("趣味");
+        assert!(ids_in_category(&app, unused).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_todo_to_json_at_includes_category() {
+        let mut app = app_with(&[(1, "a")]);
+        assert_eq!(
+            todo_to_json_at(&app, 0).to_str(),
+            r#"{"id":1,"note":"a","status":"todo","done":false}"#
+        );
+
+        let (_category, category) = c_str("仕事");
+        set_category_at(&mut app, 0, category);
+        assert_eq!(
+            todo_to_json_at(&app, 0).to_str(),
+            r#"{"id":1,"note":"a","status":"todo","done":false,"category":"仕事"}"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_category_counts() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")]);
+        let (_work, work) = c_str("仕事");
+        let (_home, home) = c_str("家");
+        set_category_at(&mut app, 0, work);
+        set_category_at(&mut app, 1, home);
+        set_category_at(&mut app, 2, work);
+
+        let json = category_counts(&app);
+        let counts: std::collections::HashMap<String, usize> =
+            serde_json::from_str(json.to_str()).unwrap();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts["仕事"], 2);
+        assert_eq!(counts["家"], 1);
+        assert_eq!(counts[""], 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_category_counts_empty() {
+        assert_eq!(category_counts(&App::default()).to_str(), "{}");
+    }
+
+    #[test]
+    fn test_copy_todo_note_truncated_at() {
+        let app = app_with(&[(1, "あいう"), (2, "abc")]);
+
+        // 「あいう」は1文字3バイト。5バイト分の空きでは「あ」だけが収まる
+        for (buf_len, expected) in [
+            (6, "あ"),
+            (7, "あい"),
+            (10, "あいう"),
+            (64, "あいう"),
+            (1, ""),
+        ] {
+            let mut buf = vec![0xffu8; buf_len];
+            let written = copy_todo_note_truncated_at(&app, 0, (&mut buf[..]).into());
+            assert_eq!(written, expected.len() as isize);
+
+            let copied = std::str::from_utf8(&buf[..written as usize]).unwrap();
+            assert_eq!(copied, expected);
+            assert_eq!(buf[written as usize], 0);
+        }
+
+        let mut buf = [0u8; 3];
+        assert_eq!(
+            copy_todo_note_truncated_at(&app, 1, (&mut buf[..]).into()),
+            2
+        );
+        assert_eq!(&buf, b"ab\0");
+    }
+
+    #[test]
+    fn test_copy_todo_note_truncated_at_edge_cases() {
+        let app = app_with(&[(1, "abc")]);
+
+        let mut buf = [0xffu8; 4];
+        assert_eq!(
+            copy_todo_note_truncated_at(&app, 1, (&mut buf[..]).into()),
+            -1
+        );
+        assert_eq!(buf, [0xff; 4]);
+
+        let mut empty: [u8; 0] = [];
+        assert_eq!(
+            copy_todo_note_truncated_at(&app, 0, (&mut empty[..]).into()),
+            0
+        );
+    }
+
+    #[cfg(feature = "varint")]
+    #[test]
+    fn test_varint_stream_roundtrip() {
+        let long_note = "あ".repeat(100);
+        let app = app_with(&[
+            (0, ""),
+            (-1, "負のID"),
+            (i32::MIN, "最小"),
+            (i32::MAX, &long_note),
+            (64, "ascii"),
+        ]);
+
+        let bytes = app_to_varint_stream(&app);
+        let restored = app_from_varint_stream(bytes[..].into());
+        assert_eq!(&restored.todos[..], &app.todos[..]);
+    }
+
+    #[cfg(feature = "varint")]
+    #[test]
+    fn test_varint_stream_zigzag_encoding() {
+        // zigzag: 0 -> 0, -1 -> 1, 1 -> 2, -2 -> 3 で、いずれも1バイトに収まる
+        let app = app_with(&[(0, ""), (-1, ""), (1, ""), (-2, "")]);
+        assert_eq!(&app_to_varint_stream(&app)[..], &[0, 0, 1, 0, 2, 0, 3, 0]);
+
+        // 長さ150のノートは長さが2バイトのvarintになる
+        let note = "a".repeat(150);
+        let app = app_with(&[(-64, &note)]);
+        let bytes = app_to_varint_stream(&app);
+        assert_eq!(&bytes[..3], &[127, 0x96, 0x01]);
+        assert_eq!(bytes.len(), 3 + 150);
+    }
+
+    #[cfg(feature = "varint")]
+    #[test]
+    fn test_app_from_varint_stream_rejects_invalid_data() {
+        let app = app_with(&[(1, "牛乳を買う"), (2, "卵")]);
+        let bytes = app_to_varint_stream(&app);
+
+        // Todoの区切り以外の位置で途切れた場合は空のアプリケーションになる
+        let boundary = 1 + 1 + "牛乳を買う".len();
+        for len in (1..bytes.len()).filter(|&len| len != boundary) {
+            let restored = app_from_varint_stream(bytes[..len].into());
+            assert!(restored.todos.is_empty(), "len = {len}");
+        }
+        assert_eq!(
+            ids_of(&app_from_varint_stream(bytes[..boundary].into())),
+            vec![1]
+        );
+
+        // 終端のないvarint、u32に収まらないID、不正なUTF-8、NULを含むノート
+        let invalid: [&[u8]; 4] = [
+            &[0x80, 0x80],
+            &[0xff, 0xff, 0xff, 0xff, 0x7f, 0],
+            &[2, 2, 0xff, 0xfe],
+            &[2, 3, b'a', 0, b'b'],
+        ];
+        for data in invalid {
+            assert!(app_from_varint_stream(data.into()).todos.is_empty());
+        }
+
+        assert!(app_from_varint_stream([][..].into()).todos.is_empty());
+    }
+
+    #[test]
+    fn test_add_todo_many_items() {
+        let mut app = App::default();
+        let (_note, note) = c_str("タスク");
+
+        assert!(add_todo(&mut app, 0, note));
+        let first_note_ptr = app.todos[0].note.to_str().as_ptr();
+
+        for id in 1..10_000 {
+            assert!(add_todo(&mut app, id, note));
+        }
+
+        assert_eq!(get_todo_count(&app), 10_000);
+        assert_eq!(get_todo_id_at(&app, 9_999), 9_999);
+        // 既存のTodoは複製されずに移動される
+        assert_eq!(app.todos[0].note.to_str().as_ptr(), first_note_ptr);
+    }
+
+    #[test]
+    fn test_remove_todo() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")]);
+
+        // 先頭
+        assert!(remove_todo(&mut app, 1));
+        assert_eq!(ids_of(&app), vec![2, 3, 4, 5]);
+
+        // 中間
+        assert!(remove_todo(&mut app, 4));
+        assert_eq!(ids_of(&app), vec![2, 3, 5]);
+
+        // 末尾
+        assert!(remove_todo(&mut app, 5));
+        assert_eq!(ids_of(&app), vec![2, 3]);
+        assert_eq!(app.todos[1].note.to_str(), "c");
+    }
+
+    #[test]
+    fn test_remove_todo_first_match_only() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (1, "c")]);
+
+        assert!(remove_todo(&mut app, 1));
+        assert_eq!(ids_of(&app), vec![2, 1]);
+        assert_eq!(app.todos[1].note.to_str(), "c");
+    }
+
+    #[test]
+    fn test_remove_todo_not_found() {
+        let mut empty = App::default();
+        assert!(!remove_todo(&mut empty, 1));
+        assert_eq!(get_todo_count(&empty), 0);
+
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        assert!(!remove_todo(&mut app, 3));
+        assert_eq!(ids_of(&app), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_todo_hash_at() {
+        let mut app = app_with(&[
+            (1, "牛乳を買う"),
+            (1, "牛乳を買う"),
+            (2, "牛乳を買う"),
+            (1, "卵を買う"),
+        ]);
+
+        let hash = todo_hash_at(&app, 0);
+        assert_ne!(hash, 0);
+        assert_eq!(todo_hash_at(&app, 1), hash);
+        // IDまたはノートが異なれば値も変わる
+        assert_ne!(todo_hash_at(&app, 2), hash);
+        assert_ne!(todo_hash_at(&app, 3), hash);
+
+        // 状態はハッシュに影響しない
+        set_status_at(&mut app, 1, Status::Done);
+        assert_eq!(todo_hash_at(&app, 1), hash);
+
+        // ノートを変更すると値も変わる
+        assert!(set_note_from_bytes_at(
+            &mut app,
+            0,
+            b"changed".as_slice().into()
+        ));
+        assert_ne!(todo_hash_at(&app, 0), hash);
+
+        assert_eq!(todo_hash_at(&app, 4), 0);
+    }
+
+    #[test]
+    fn test_todo_hash_at_is_stable() {
+        // 実行ごとに値が変わらないことを固定値で確認する
+        let app = app_with(&[(1, "a")]);
+        assert_eq!(todo_hash_at(&app, 0), 0xd80d_0dae_a7db_dd7f);
+    }
+
+    #[test]
+    fn test_update_todo_note() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (2, "c")]);
+        let (_note, note) = c_str("更新後");
+
+        assert!(update_todo_note(&mut app, 2, note));
+        assert_eq!(ids_of(&app), vec![1, 2, 2]);
+        assert_eq!(app.todos[1].note.to_str(), "更新後");
+        assert_eq!(app.todos[2].note.to_str(), "c");
+        assert_eq!(app.todos[0].note.to_str(), "a");
+
+        // 元の文字列とは別に確保される
+        assert_ne!(app.todos[1].note.to_str().as_ptr(), note.to_str().as_ptr());
+    }
+
+    #[test]
+    fn test_update_todo_note_not_found() {
+        let mut app = app_with(&[(1, "a")]);
+        let (_note, note) = c_str("更新後");
+
+        assert!(!update_todo_note(&mut app, 2, note));
+        assert_eq!(app.todos[0].note.to_str(), "a");
+        assert!(!update_todo_note(&mut App::default(), 1, note));
+    }
+
+    #[test]
+    fn test_float_matching_to_top() {
+        let mut app = app_with(&[
+            (1, "a"),
+            (2, "至急: b"),
+            (3, "c"),
+            (4, "d 至急"),
+            (5, "e"),
+            (6, "至急"),
+        ]);
+        let (_needle, needle) = c_str("至急");
+
+        assert_eq!(float_matching_to_top(&mut app, needle), 3);
+        assert_eq!(ids_of(&app), vec![2, 4, 6, 1, 3, 5]);
+
+        // 一致しない場合は順番を変えない
+        let (_missing, missing) = c_str("存在しない");
+        assert_eq!(float_matching_to_top(&mut app, missing), 0);
+        assert_eq!(ids_of(&app), vec![2, 4, 6, 1, 3, 5]);
+    }
+
+    #[test]
+    fn test_float_matching_to_top_empty_needle() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        let (_needle, needle) = c_str("");
+
+        assert_eq!(float_matching_to_top(&mut app, needle), 0);
+        assert_eq!(ids_of(&app), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_set_todo_done() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        assert!(!is_todo_done(&app, 1));
+        assert!(!app.todos[0].done);
+
+        assert!(set_todo_done(&mut app, 1, true));
+        assert!(is_todo_done(&app, 1));
+        assert!(!is_todo_done(&app, 2));
+        assert_eq!(app.todos[0].status, Status::Done);
+
+        assert!(set_todo_done(&mut app, 1, false));
+        assert!(!is_todo_done(&app, 1));
+        assert_eq!(app.todos[0].status, Status::Todo);
+
+        assert!(!set_todo_done(&mut app, 3, true));
+        assert!(!is_todo_done(&app, 3));
+    }
+
+    #[test]
+    fn test_done_follows_status() {
+        let mut app = app_with(&[(1, "a")]);
+
+        assert!(set_status_at(&mut app, 0, Status::Done));
+        assert!(is_todo_done(&app, 1));
+
+        // Done -> Todo
+        assert!(cycle_status_at(&mut app, 0));
+        assert!(!is_todo_done(&app, 1));
+
+        // 作業中のTodoは未完了のまま変わらない
+        assert!(set_status_at(&mut app, 0, Status::Doing));
+        assert!(set_todo_done(&mut app, 1, false));
+        assert_eq!(app.todos[0].status, Status::Doing);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_update_todo_from_json_at_done() {
+        let mut app = app_with(&[(1, "a")]);
+
+        let (_done, done) = c_str(r#"{"id":1,"note":"a","done":true}"#);
+        assert!(update_todo_from_json_at(&mut app, 0, done));
+        assert!(app.todos[0].done);
+        assert_eq!(app.todos[0].status, Status::Done);
+
+        let (_status, status) = c_str(r#"{"id":1,"note":"a","status":"done"}"#);
+        set_todo_done(&mut app, 1, false);
+        assert!(update_todo_from_json_at(&mut app, 0, status));
+        assert!(app.todos[0].done);
+    }
+
+    #[test]
+    fn test_app_note_at() {
+        let app = app_with(&[(1, "牛乳を買う"), (2, "")]);
+
+        assert_eq!(app.note_at(0), Some("牛乳を買う".to_string()));
+        assert_eq!(app.note_at(1), Some(String::new()));
+        assert_eq!(app.note_at(2), None);
+        assert_eq!(App::default().note_at(0), None);
+    }
+
+    #[test]
+    fn test_try_get_todo_id_at() {
+        let app = app_with(&[(42, "a"), (-1, "b")]);
+
+        assert_eq!(
+            try_get_todo_id_at(&app, 0),
+            OptionalId {
+                has_value: true,
+                value: 42
+            }
+        );
+        // IDが-1のTodoも範囲外と区別できる
+        assert_eq!(
+            try_get_todo_id_at(&app, 1),
+            OptionalId {
+                has_value: true,
+                value: -1
+            }
+        );
+        assert!(!try_get_todo_id_at(&app, 2).has_value);
+        assert!(!try_get_todo_id_at(&App::default(), 0).has_value);
+
+        // 従来の関数の挙動は変わらない
+        assert_eq!(get_todo_id_at(&app, 0), 42);
+        assert_eq!(get_todo_id_at(&app, 1), -1);
+        assert_eq!(get_todo_id_at(&app, 2), -1);
+    }
+
+    extern "C" fn record_indexed(
+        index: usize,
+        id: i32,
+        note: char_p::Ref<'_>,
+        user_data: *mut c_void,
+    ) {
+        let rows = unsafe { &mut *(user_data as *mut Vec<(usize, i32, String)>) };
+        rows.push((index, id, note.to_str().to_string()));
+    }
+
+    #[test]
+    fn test_for_each_todo_indexed() {
+        let app = app_with(&[(3, "c"), (1, "a"), (2, "b")]);
+
+        let mut rows: Vec<(usize, i32, String)> = Vec::new();
+        for_each_todo_indexed(&app, record_indexed, &mut rows as *mut _ as *mut c_void);
+
+        let indices: Vec<usize> = rows.iter().map(|(index, _, _)| *index).collect();
+        assert_eq!(indices, (0..app.todos.len()).collect::<Vec<_>>());
+        assert_eq!(rows[0], (0, 3, "c".to_string()));
+        assert_eq!(rows[2], (2, 2, "b".to_string()));
+
+        let mut rows: Vec<(usize, i32, String)> = Vec::new();
+        for_each_todo_indexed(
+            &App::default(),
+            record_indexed,
+            &mut rows as *mut _ as *mut c_void,
+        );
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_clear_todos() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        clear_todos(&mut app);
+        assert_eq!(get_todo_count(&app), 0);
+
+        // 空の状態でも呼び出せる
+        clear_todos(&mut app);
+        assert_eq!(get_todo_count(&app), 0);
+
+        // 削除後も追加できる
+        let (_note, note) = c_str("新しいタスク");
+        assert!(add_todo(&mut app, 4, note));
+        assert_eq!(ids_of(&app), vec![4]);
+        assert_eq!(app.todos[0].note.to_str(), "新しいタスク");
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("temp*", "temp"));
+        assert!(glob_match("temp*", "temporary"));
+        assert!(!glob_match("temp*", "a temp"));
+        assert!(glob_match("*.txt", "メモ.txt"));
+        assert!(glob_match("a?c", "abc"));
+        assert!(glob_match("a?c", "aあc"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(glob_match("*a*b*", "xxaxxbxx"));
+        assert!(!glob_match("*a*b", "xxbxxa"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn test_replace_notes_matching_glob() {
+        let mut app = app_with(&[
+            (1, "temp1"),
+            (2, "牛乳を買う"),
+            (3, "temporary"),
+            (4, "not temp"),
+        ]);
+        let (_pattern, pattern) = c_str("temp*");
+        let (_replacement, replacement) = c_str("置換後");
+
+        assert_eq!(
+            replace_notes_matching_glob(&mut app, pattern, replacement),
+            2
+        );
+        assert_eq!(app.todos[0].note.to_str(), "置換後");
+        assert_eq!(app.todos[2].note.to_str(), "置換後");
+        // 一致しないノートは変わらない
+        assert_eq!(app.todos[1].note.to_str(), "牛乳を買う");
+        assert_eq!(app.todos[3].note.to_str(), "not temp");
+        assert_eq!(ids_of(&app), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_replace_notes_matching_glob_no_match() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        let (_pattern, pattern) = c_str("z*");
+        let (_replacement, replacement) = c_str("置換後");
+
+        assert_eq!(
+            replace_notes_matching_glob(&mut app, pattern, replacement),
+            0
+        );
+        assert_eq!(app.todos[0].note.to_str(), "a");
+        assert_eq!(app.todos[1].note.to_str(), "b");
+    }
+
+    #[test]
+    fn test_add_todo_checked() {
+        let mut app = App::default();
+        let (_note, note) = c_str("タスク");
+
+        assert_eq!(add_todo_checked(&mut app, 1, note), AddResult::Ok);
+        assert_eq!(add_todo_checked(&mut app, 2, note), AddResult::Ok);
+        assert_eq!(add_todo_checked(&mut app, 1, note), AddResult::DuplicateId);
+        assert_eq!(ids_of(&app), vec![1, 2]);
+
+        // add_todo も重複したIDを拒否する
+        assert!(!add_todo(&mut app, 2, note));
+        assert!(add_todo(&mut app, 3, note));
+        assert_eq!(ids_of(&app), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_add_todo_checked_note_with_nul() {
+        // char_p::Ref は最初のNULで終端されるため、途中にNULを含むバッファは
+        // その手前までのノートとして扱われ、パニックしない
+        let bytes = b"ab\0cd\0";
+        let c_str = std::ffi::CStr::from_bytes_until_nul(bytes).unwrap();
+        let mut app = App::default();
+
+        assert_eq!(
+            add_todo_checked(&mut app, 1, char_p::Ref::from(c_str)),
+            AddResult::Ok
+        );
+        assert_eq!(app.todos[0].note.to_str(), "ab");
+
+        // NULを含む文字列からは char_p::Ref を作れない
+        assert!(std::ffi::CString::new("ab\0cd").is_err());
+    }
+
+    #[test]
+    fn test_offset_all_ids() {
+        let mut app = app_with(&[(1, "a"), (-5, "b"), (30, "c")]);
+
+        assert_eq!(offset_all_ids(&mut app, 100), 0);
+        assert_eq!(ids_of(&app), vec![101, 95, 130]);
+
+        assert_eq!(offset_all_ids(&mut app, -200), 0);
+        assert_eq!(ids_of(&app), vec![-99, -105, -70]);
+
+        assert_eq!(offset_all_ids(&mut app, 0), 0);
+        assert_eq!(ids_of(&app), vec![-99, -105, -70]);
+    }
+
+    #[test]
+    fn test_offset_all_ids_saturates() {
+        let mut app = app_with(&[(i32::MAX - 1, "a"), (0, "b"), (i32::MAX, "c")]);
+        assert_eq!(offset_all_ids(&mut app, 10), 2);
+        assert_eq!(ids_of(&app), vec![i32::MAX, 10, i32::MAX]);
+
+        let mut app = app_with(&[(i32::MIN + 1, "a"), (0, "b")]);
+        assert_eq!(offset_all_ids(&mut app, -5), 1);
+        assert_eq!(ids_of(&app), vec![i32::MIN, -5]);
+    }
+
+    #[test]
+    fn test_todo_try_new() {
+        let todo = Todo::try_new(1, "牛乳を買う").unwrap();
+        assert_eq!(todo, Todo::new(1, "牛乳を買う"));
+
+        // NULを含む場合もパニックせずにエラーを返す
+        let result = std::panic::catch_unwind(|| Todo::try_new(2, "ab\0cd"));
+        let err = result.expect("try_new はパニックしない").unwrap_err();
+        assert_eq!(err.nul_position(), 2);
+    }
+
+    #[test]
+    fn test_ids_with_same_note_as() {
+        let app = app_with(&[(1, "a"), (2, "b"), (3, "a"), (4, "a "), (5, "c")]);
+
+        assert_eq!(&ids_with_same_note_as(&app, 0)[..], &[3]);
+        assert_eq!(&ids_with_same_note_as(&app, 2)[..], &[1]);
+        assert!(ids_with_same_note_as(&app, 1).is_empty());
+        assert!(ids_with_same_note_as(&app, 5).is_empty());
+
+        // 自身と同じIDを持つ別のTodoは含まれる
+        let same_id = app_with(&[(1, "x"), (1, "x")]);
+        assert_eq!(&ids_with_same_note_as(&same_id, 0)[..], &[1]);
+    }
+
+    #[test]
+    fn test_find_todo_index_by_id() {
+        let app = app_with(&[(10, "a"), (20, "b"), (30, "c")]);
+        assert_eq!(find_todo_index_by_id(&app, 10), 0);
+        assert_eq!(find_todo_index_by_id(&app, 30), 2);
+        assert_eq!(find_todo_index_by_id(&app, 40), -1);
+
+        // 同じIDが複数ある場合は最初の1件
+        let duplicated = app_with(&[(1, "a"), (2, "b"), (2, "c")]);
+        assert_eq!(find_todo_index_by_id(&duplicated, 2), 1);
+
+        assert_eq!(find_todo_index_by_id(&App::default(), 1), -1);
+    }
+
+    #[test]
+    fn test_ids_only_in_first() {
+        let a = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+
+        let same = app_with(&[(3, "x"), (2, "y"), (1, "z")]);
+        assert!(ids_only_in_first(&a, &same).is_empty());
+
+        let partial = app_with(&[(2, "b"), (4, "d")]);
+        assert_eq!(&ids_only_in_first(&a, &partial)[..], &[1, 3]);
+        assert_eq!(&ids_only_in_first(&partial, &a)[..], &[4]);
+
+        assert_eq!(&ids_only_in_first(&a, &App::default())[..], &[1, 2, 3]);
+        assert!(ids_only_in_first(&App::default(), &a).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_to_json() {
+        let mut app = app_with(&[
+            (1, "牛乳を買う"),
+            (2, r#"say "hi" \ bye"#),
+            (-3, "line1\nline2\ttab"),
+        ]);
+        set_todo_done(&mut app, 2, true);
+
+        let json = app_to_json(&app);
+        assert_eq!(
+            json.to_str(),
+            concat!(
+                r#"[{"id":1,"note":"牛乳を買う","status":"todo","done":false},"#,
+                r#"{"id":2,"note":"say \"hi\" \\ bye","status":"done","done":true},"#,
+                r#"{"id":-3,"note":"line1\nline2\ttab","status":"todo","done":false}]"#
+            )
+        );
+
+        // 出力したJSONを読み込むと元のノートに戻る
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(json.to_str()).unwrap();
+        let notes: Vec<&str> = parsed.iter().map(|v| v["note"].as_str().unwrap()).collect();
+        assert_eq!(
+            notes,
+            vec!["牛乳を買う", r#"say "hi" \ bye"#, "line1\nline2\ttab"]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_to_json_empty() {
+        assert_eq!(app_to_json(&App::default()).to_str(), "[]");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_roundtrip_ok() {
+        let ordinary = app_with(&[(1, "牛乳を買う"), (2, "レポートを書く")]);
+        assert!(json_roundtrip_ok(&ordinary));
+
+        let escaped = app_with(&[
+            (1, r#"say "hi""#),
+            (2, "line1\nline2\r\n"),
+            (3, r"C:\path\to\file"),
+            (4, "\u{1}\t\u{1f}"),
+        ]);
+        assert!(json_roundtrip_ok(&escaped));
+
+        let mut multibyte = app_with(&[(-1, "日本語のメモ🍣"), (i32::MAX, "Ünïcödé")]);
+        set_todo_done(&mut multibyte, -1, true);
+        let (_category, category) = c_str("分類");
+        set_category_at(&mut multibyte, 1, category);
+        assert!(json_roundtrip_ok(&multibyte));
+
+        assert!(json_roundtrip_ok(&App::default()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_from_json_roundtrip() {
+        let mut app = app_with(&[(1, "牛乳を買う"), (2, r#"say "hi""#), (-3, "a\\b\nc")]);
+        set_todo_done(&mut app, 2, true);
+
+        let json = app_to_json(&app);
+        let restored = app_from_json(json.as_ref());
+
+        assert_eq!(get_todo_count(&restored), 3);
+        assert_eq!(ids_of(&restored), vec![1, 2, -3]);
+        for i in 0..3 {
+            assert_eq!(restored.note_at(i), app.note_at(i));
+        }
+        assert!(is_todo_done(&restored, 2));
+        assert!(!is_todo_done(&restored, 1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_from_json_missing_done() {
+        let (_json, json) = c_str(r#"[{"id":1,"note":"a"},{"id":2,"note":"b","status":"doing"}]"#);
+        let app = app_from_json(json);
+
+        assert_eq!(ids_of(&app), vec![1, 2]);
+        assert!(!app.todos[0].done);
+        assert!(!app.todos[1].done);
+        assert_eq!(app.todos[1].status, Status::Doing);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_app_from_json_malformed() {
+        for input in [
+            "",
+            "[",
+            "not json",
+            r#"{"id":1,"note":"a"}"#,
+            r#"[{"id":"1","note":"a"}]"#,
+            r#"[{"id":1,"note":"a\u0000b"}]"#,
+        ] {
+            let (_json, json) = c_str(input);
+            assert_eq!(get_todo_count(&app_from_json(json)), 0, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_add_todo_alnum_only() {
+        let mut app = App::default();
+
+        let (_valid, valid) = c_str("Buy milk 2");
+        assert!(add_todo_alnum_only(&mut app, 1, valid));
+
+        // マルチバイトの文字も英数字として扱う
+        let (_multibyte, multibyte) = c_str("牛乳を買う 2本");
+        assert!(add_todo_alnum_only(&mut app, 2, multibyte));
+
+        for (id, note) in [
+            (3, "Buy milk!"),
+            (4, "a-b"),
+            (5, "改行\nあり"),
+            (6, "牛乳、卵"),
+        ] {
+            let (_note, note) = c_str(note);
+            assert!(!add_todo_alnum_only(&mut app, id, note));
+        }
+
+        assert_eq!(ids_of(&app), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_add_todos() {
+        let mut app = app_with(&[(1, "既存")]);
+        let (_a, a) = c_str("a");
+        let (_b, b) = c_str("b");
+        let (_c, c) = c_str("c");
+
+        let notes = [a, b, c];
+        assert_eq!(
+            add_todos(&mut app, [2, 3, 4][..].into(), notes[..].into()),
+            3
+        );
+        assert_eq!(ids_of(&app), vec![1, 2, 3, 4]);
+        assert_eq!(app.todos[3].note.to_str(), "c");
+    }
+
+    #[test]
+    fn test_add_todos_mismatched_lengths() {
+        let (_a, a) = c_str("a");
+        let (_b, b) = c_str("b");
+        let (_c, c) = c_str("c");
+
+        // ノートの方が短い
+        let mut app = App::default();
+        let notes = [a, b];
+        assert_eq!(
+            add_todos(&mut app, [1, 2, 3][..].into(), notes[..].into()),
+            2
+        );
+        assert_eq!(ids_of(&app), vec![1, 2]);
+
+        // IDの方が短い
+        let mut app = App::default();
+        let notes = [a, b, c];
+        assert_eq!(add_todos(&mut app, [7][..].into(), notes[..].into()), 1);
+        assert_eq!(ids_of(&app), vec![7]);
+        assert_eq!(app.todos[0].note.to_str(), "a");
+    }
+
+    #[test]
+    fn test_add_todos_empty_and_duplicates() {
+        let mut app = app_with(&[(1, "既存")]);
+        let no_notes: [char_p::Ref<'_>; 0] = [];
+        assert_eq!(add_todos(&mut app, [][..].into(), no_notes[..].into()), 0);
+        assert_eq!(ids_of(&app), vec![1]);
+
+        // 既存のIDや、同じ呼び出しの中で重複するIDは追加しない
+        let (_a, a) = c_str("a");
+        let notes = [a, a, a];
+        assert_eq!(
+            add_todos(&mut app, [1, 2, 2][..].into(), notes[..].into()),
+            1
+        );
+        assert_eq!(ids_of(&app), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_total_word_count() {
+        let app = app_with(&[
+            (1, "buy some milk"),
+            (2, "  call\tmom \n tonight "),
+            (3, ""),
+            (4, "   "),
+            (5, "牛乳を買う"),
+        ]);
+        // 空のノートと空白のみのノートは0語として数える
+        assert_eq!(total_word_count(&app), 7);
+
+        assert_eq!(total_word_count(&App::default()), 0);
+    }
+
+    #[test]
+    fn test_pick_index() {
+        let app = app_with(&[
+            (1, "a"),
+            (2, "b"),
+            (3, "c"),
+            (4, "d"),
+            (5, "e"),
+            (6, "f"),
+            (7, "g"),
+        ]);
+
+        let mut picked = HashSet::new();
+        for seed in 0..200 {
+            let index = pick_index(&app, seed);
+            assert!((0..7).contains(&index), "seed = {seed}");
+            // 同じシード値からは常に同じインデックス
+            assert_eq!(pick_index(&app, seed), index);
+            // 内容ではなく件数のみに依存する
+            assert_eq!(pick_index(&app_with(&[(0, ""); 7]), seed), index);
+            picked.insert(index);
+        }
+        // シード値を変えると偏りなく選ばれる
+        assert_eq!(picked.len(), 7);
+
+        let single = app_with(&[(1, "a")]);
+        assert_eq!(pick_index(&single, u64::MAX), 0);
+    }
+
+    #[test]
+    fn test_pick_index_is_stable() {
+        // 実行ごとに値が変わらないことを固定値で確認する
+        let app = app_with(&[(0, ""); 10]);
+        let picks: Vec<isize> = (0..5).map(|seed| pick_index(&app, seed)).collect();
+        assert_eq!(picks, vec![5, 5, 0, 3, 8]);
+    }
+
+    #[test]
+    fn test_pick_index_empty() {
+        assert_eq!(pick_index(&App::default(), 0), -1);
+        assert_eq!(pick_index(&App::default(), 12345), -1);
+    }
+
+    #[test]
+    fn test_add_todo_auto() {
+        let mut app = App::default();
+        assert_eq!(app.next_id, 1);
+
+        let (_note, note) = c_str("タスク");
+        let ids: Vec<i32> = (0..3).map(|_| add_todo_auto(&mut app, note)).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(get_todo_count(&app), 3);
+
+        // 削除したIDは再利用されない
+        assert!(remove_todo(&mut app, 2));
+        assert!(remove_todo(&mut app, 3));
+        assert_eq!(add_todo_auto(&mut app, note), 4);
+        assert_eq!(
+            app.todos.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![1, 4]
+        );
+    }
+
+    #[test]
+    fn test_add_todo_auto_skips_used_ids() {
+        let mut app = App::default();
+        let (_note, note) = c_str("タスク");
+        add_todo(&mut app, 1, note);
+        add_todo(&mut app, 2, note);
+
+        assert_eq!(add_todo_auto(&mut app, note), 3);
+        assert_eq!(app.next_id, 4);
+
+        // IDを使い切った場合は追加しない
+        app.next_id = i32::MAX;
+        assert_eq!(add_todo_auto(&mut app, note), -1);
+        assert_eq!(get_todo_count(&app), 3);
+    }
+
+    // テストごとに異なる一時ファイルのパスを作成するヘルパー関数
+    fn temp_path(name: &str) -> (std::path::PathBuf, std::ffi::CString) {
+        let path = std::env::temp_dir().join(format!(
+            "safer_ffi_example_{}_{name}.bin",
+            std::process::id()
+        ));
+        let c_path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+        (path, c_path)
+    }
+
+    // すべてのフィールドに既定値以外の値を持つTodoを含むアプリケーションを作成するヘルパー関数
+    fn app_with_all_fields() -> App {
+        let mut app = app_with(&[(1, "牛乳を買う"), (-5, ""), (300, "レポート")]);
+        app.todos[0].set_status(Status::Doing);
+        app.todos[0].category = "買い物".to_string().try_into().unwrap();
+        app.todos[0].priority = Priority::High;
+        app.todos[1].set_done(true);
+        app.todos[1].priority = Priority::Low;
+        app.todos[2].category = "仕事".to_string().try_into().unwrap();
+        app
+    }
+
+    #[test]
+    fn test_app_save_and_load_versioned() {
+        let (path, c_path) = temp_path("save_and_load_versioned");
+        let app = app_with_all_fields();
+
+        assert!(app_save_versioned(&app, char_p::Ref::from(c_path.as_ref())));
+
+        // 先頭にスキーマバージョンが書き込まれる
+        let data = std::fs::read(&path).unwrap();
+        assert_eq!(data[..2], APP_SCHEMA_VERSION.to_le_bytes());
+
+        // すべてのフィールドが復元される
+        let restored = app_load_versioned(char_p::Ref::from(c_path.as_ref()));
+        assert_eq!(restored.todos.len(), 3);
+        for (restored, original) in restored.todos.iter().zip(app.todos.iter()) {
+            assert_eq!(restored.id, original.id);
+            assert_eq!(restored.note, original.note);
+            assert_eq!(restored.status, original.status);
+            assert_eq!(restored.done, original.done);
+            assert_eq!(restored.category, original.category);
+            assert_eq!(restored.priority, original.priority);
+        }
+
+        // 空のアプリケーションはバージョンのみのファイルになる
+        assert!(app_save_versioned(
+            &App::default(),
+            char_p::Ref::from(c_path.as_ref())
+        ));
+        assert_eq!(std::fs::read(&path).unwrap().len(), 2);
+        assert!(app_load_versioned(char_p::Ref::from(c_path.as_ref()))
+            .todos
+            .is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_app_load_versioned_rejects_unknown_version() {
+        let (path, c_path) = temp_path("unknown_version");
+        let mut data = encode_versioned(&app_with(&[(1, "a")]));
+
+        for version in [0, APP_SCHEMA_VERSION + 1] {
+            data[..2].copy_from_slice(&version.to_le_bytes());
+            std::fs::write(&path, &data).unwrap();
+            assert!(app_load_versioned(char_p::Ref::from(c_path.as_ref()))
+                .todos
+                .is_empty());
+        }
+
+        // バージョンが途中で終わっている場合
+        std::fs::write(&path, [APP_SCHEMA_VERSION as u8]).unwrap();
+        assert!(app_load_versioned(char_p::Ref::from(c_path.as_ref()))
+            .todos
+            .is_empty());
+
+        // ファイルが存在しない場合
+        std::fs::remove_file(&path).unwrap();
+        assert!(app_load_versioned(char_p::Ref::from(c_path.as_ref()))
+            .todos
+            .is_empty());
+    }
+
+    #[test]
+    fn test_decode_versioned_rejects_invalid_fields() {
+        let valid = encode_versioned(&app_with(&[(1, "a")]));
+        assert!(decode_versioned(&valid).is_some());
+        // ID、ノート（長さ+1バイト）の後に状態、完了フラグ、分類（長さのみ）、優先度が続く
+        let (status, done, priority) = (2 + 1 + 2, 2 + 1 + 3, 2 + 1 + 5);
+        assert_eq!(valid.len(), priority + 1);
+
+        let mut bad_status = valid.clone();
+        bad_status[status] = 3;
+        let mut bad_done = valid.clone();
+        bad_done[done] = 2;
+        // 状態が完了でないのに完了フラグが立っている
+        let mut inconsistent = valid.clone();
+        inconsistent[done] = 1;
+        let mut bad_priority = valid.clone();
+        bad_priority[priority] = 3;
+
+        for data in [bad_status, bad_done, inconsistent, bad_priority] {
+            assert!(decode_versioned(&data).is_none());
+        }
+        // バージョンのみの場合は空のアプリケーションとして扱い、途中で終わっている場合は不正
+        for len in 3..valid.len() {
+            assert!(decode_versioned(&valid[..len]).is_none(), "len = {len}");
+        }
+    }
+
+    #[test]
+    fn test_get_all_todo_ids() {
+        let app = app_with(&[(5, "a"), (-1, "b"), (5, "c"), (2, "d")]);
+        let ids = get_all_todo_ids(&app);
+        assert_eq!(&ids[..], &[5, -1, 5, 2]);
+        free_id_slice(ids);
+    }
+
+    #[test]
+    fn test_get_all_todo_ids_empty() {
+        let ids = get_all_todo_ids(&App::default());
+        // c_slice::Boxは空でもNULLでないポインタを保持する
+        assert_eq!(ids.len(), 0);
+        free_id_slice(ids);
     }
 
     #[test]
-    fn test_todo_new() {
-        let todo = Todo::new(42, "テストタスク");
-        assert_eq!(todo.id, 42);
-        assert_eq!(todo.note.to_str(), "テストタスク");
+    fn test_most_frequent_id() {
+        let app = app_with(&[(3, "a"), (7, "b"), (3, "c"), (1, "d"), (3, "e"), (7, "f")]);
+        let (mut id, mut count) = (0, 0);
+        assert!(most_frequent_id(&app, &mut id, &mut count));
+        assert_eq!((id, count), (3, 3));
     }
 
     #[test]
-    fn test_app_new() {
-        let app = App::default();
-        assert_eq!(app.todos.len(), 0);
+    fn test_most_frequent_id_tie_picks_smallest() {
+        let app = app_with(&[(5, "a"), (-2, "b"), (9, "c"), (5, "d"), (-2, "e"), (9, "f")]);
+        let (mut id, mut count) = (0, 0);
+        assert!(most_frequent_id(&app, &mut id, &mut count));
+        assert_eq!((id, count), (-2, 2));
+
+        // すべて1回ずつの場合も最小のIDになる
+        let app = app_with(&[(4, "a"), (2, "b"), (8, "c")]);
+        assert!(most_frequent_id(&app, &mut id, &mut count));
+        assert_eq!((id, count), (2, 1));
     }
 
     #[test]
-    fn test_add_todo() {
+    fn test_most_frequent_id_empty() {
+        let (mut id, mut count) = (42, 42);
+        assert!(!most_frequent_id(&App::default(), &mut id, &mut count));
+        // 書き込み先は変更されない
+        assert_eq!((id, count), (42, 42));
+    }
+
+    #[test]
+    fn test_sort_todos_by_id() {
+        let mut app = app_with(&[(3, "c"), (-1, "x"), (2, "b1"), (10, "z"), (2, "b2")]);
+
+        sort_todos_by_id(&mut app, true);
+        assert_eq!(ids_of(&app), vec![-1, 2, 2, 3, 10]);
+        // 同じIDのTodoは元の順番のまま
+        assert_eq!(app.note_at(1), Some("b1".to_string()));
+        assert_eq!(app.note_at(2), Some("b2".to_string()));
+
+        sort_todos_by_id(&mut app, false);
+        assert_eq!(ids_of(&app), vec![10, 3, 2, 2, -1]);
+        assert_eq!(app.note_at(2), Some("b1".to_string()));
+        assert_eq!(app.note_at(3), Some("b2".to_string()));
+
+        let mut empty = App::default();
+        sort_todos_by_id(&mut empty, true);
+        assert!(empty.todos.is_empty());
+    }
+
+    #[test]
+    fn test_trim_all_notes() {
+        let mut app = app_with(&[
+            (1, "  前後に空白  "),
+            (2, "そのまま"),
+            (3, "\t改行\r\n"),
+            (4, "　全角スペース　"),
+            (5, "   "),
+            (6, ""),
+            (7, "中 の 空白"),
+        ]);
+
+        assert_eq!(trim_all_notes(&mut app), 4);
+        let notes: Vec<_> = (0..7).map(|i| app.note_at(i).unwrap()).collect();
+        assert_eq!(
+            notes,
+            vec![
+                "前後に空白",
+                "そのまま",
+                "改行",
+                "全角スペース",
+                "",
+                "",
+                "中 の 空白"
+            ]
+        );
+        assert_eq!(ids_of(&app), vec![1, 2, 3, 4, 5, 6, 7]);
+
+        // 2回目は何も変わらない
+        assert_eq!(trim_all_notes(&mut app), 0);
+    }
+
+    #[test]
+    fn test_sort_todos_by_note() {
+        let mut app = app_with(&[
+            (1, "漢字"),
+            (2, "banana"),
+            (3, "カタカナ"),
+            (4, "Apple"),
+            (5, "ひらがな"),
+            (6, "apple"),
+            (7, "banana"),
+            (8, ""),
+        ]);
+
+        // コードポイント順（ロケールによる照合ではない）
+        sort_todos_by_note(&mut app, true);
+        assert_eq!(ids_of(&app), vec![8, 4, 6, 2, 7, 5, 3, 1]);
+
+        // 降順でも同じノートのTodoは元の順番のまま
+        sort_todos_by_note(&mut app, false);
+        assert_eq!(ids_of(&app), vec![1, 3, 5, 2, 7, 6, 4, 8]);
+    }
+
+    #[test]
+    fn test_filter_todos_contains() {
+        let app = app_with(&[(1, "牛乳を買う"), (2, "レポートを書く"), (3, "卵を買う")]);
+
+        let (_s, substring) = c_str("買う");
+        let filtered = filter_todos_contains(&app, substring);
+        assert_eq!(ids_of(&filtered), vec![1, 3]);
+        assert_eq!(filtered.note_at(1), Some("卵を買う".to_string()));
+        app_free(filtered);
+
+        // 元のアプリケーションは変更されない
+        assert_eq!(ids_of(&app), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_filter_todos_contains_empty_substring() {
+        let app = app_with(&[(1, "a"), (2, ""), (3, "b")]);
+        let (_s, substring) = c_str("");
+        let filtered = filter_todos_contains(&app, substring);
+        assert_eq!(ids_of(&filtered), vec![1, 2, 3]);
+        app_free(filtered);
+    }
+
+    #[test]
+    fn test_filter_todos_contains_no_match() {
+        let app = app_with(&[(1, "a"), (2, "b")]);
+        let (_s, substring) = c_str("存在しない");
+        let filtered = filter_todos_contains(&app, substring);
+        assert!(filtered.todos.is_empty());
+        app_free(filtered);
+        assert_eq!(get_todo_count(&app), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_notes_by_index_json() {
+        let app = app_with(&[(5, "牛乳を買う"), (9, r#"「"引用"」\ を含む"#)]);
+        assert_eq!(
+            notes_by_index_json(&app).to_str(),
+            r#"{"0":"牛乳を買う","1":"「\"引用\"」\\ を含む"}"#
+        );
+
+        // 10件以上でもインデックス順に並ぶ
+        let items: Vec<(i32, &str)> = (0..11).map(|id| (id, "n")).collect();
+        let json = notes_by_index_json(&app_with(&items));
+        assert!(json.to_str().starts_with(r#"{"0":"n","1":"n","2":"n""#));
+        assert!(json.to_str().ends_with(r#""9":"n","10":"n"}"#));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_notes_by_index_json_empty() {
+        assert_eq!(notes_by_index_json(&App::default()).to_str(), "{}");
+    }
+
+    #[test]
+    fn test_sync_app_concurrent_add() {
+        const THREADS: i32 = 8;
+        const PER_THREAD: i32 = 100;
+
+        let app = sync_app_new();
+        std::thread::scope(|s| {
+            for t in 0..THREADS {
+                let app = &app;
+                s.spawn(move || {
+                    let (_note, note) = c_str("タスク");
+                    for i in 0..PER_THREAD {
+                        assert!(sync_add_todo(app, t * PER_THREAD + i, note));
+                    }
+                });
+            }
+        });
+
+        assert_eq!(sync_get_todo_count(&app), (THREADS * PER_THREAD) as usize);
+
+        // 同じIDは追加されない
+        let (_note, note) = c_str("重複");
+        assert!(!sync_add_todo(&app, 0, note));
+        assert_eq!(sync_get_todo_count(&app), (THREADS * PER_THREAD) as usize);
+        sync_app_free(app);
+    }
+
+    #[test]
+    fn test_get_todo_note_at_into() {
+        let app = app_with(&[(1, "牛乳を買う"), (2, "")]);
+
+        let mut buf = [0xffu8; 32];
+        assert_eq!(get_todo_note_at_into(&app, 0, (&mut buf[..]).into()), 15);
+        assert_eq!(&buf[..16], "牛乳を買う\0".as_bytes());
+        // 書き込んだ範囲より後ろは変更されない
+        assert_eq!(buf[16], 0xff);
+
+        // ちょうどNUL終端まで収まる長さ
+        let mut exact = [0xffu8; 16];
+        assert_eq!(get_todo_note_at_into(&app, 0, (&mut exact[..]).into()), 15);
+        assert_eq!(exact[15], 0);
+
+        // 空のノートはNUL終端のみ
+        let mut one = [0xffu8; 1];
+        assert_eq!(get_todo_note_at_into(&app, 1, (&mut one[..]).into()), 0);
+        assert_eq!(one, [0]);
+    }
+
+    #[test]
+    fn test_get_todo_note_at_into_small_buffer() {
+        let app = app_with(&[(1, "牛乳を買う"), (2, "")]);
+
+        // NUL終端の分だけ足りない
+        let mut buf = [0xffu8; 15];
+        assert_eq!(get_todo_note_at_into(&app, 0, (&mut buf[..]).into()), -1);
+        assert_eq!(buf, [0xff; 15]);
+
+        let mut empty: [u8; 0] = [];
+        assert_eq!(get_todo_note_at_into(&app, 1, (&mut empty[..]).into()), -1);
+    }
+
+    #[test]
+    fn test_get_todo_note_at_into_out_of_range() {
+        let app = app_with(&[(1, "a")]);
+        let mut buf = [0xffu8; 8];
+        assert_eq!(get_todo_note_at_into(&app, 1, (&mut buf[..]).into()), -2);
+        assert_eq!(buf, [0xff; 8]);
+
+        // バッファが足りなくても範囲外を優先して報告する
+        let mut empty: [u8; 0] = [];
+        assert_eq!(
+            get_todo_note_at_into(&App::default(), 0, (&mut empty[..]).into()),
+            -2
+        );
+    }
+
+    #[test]
+    fn test_set_on_change() {
+        use std::sync::Mutex;
+
+        // コールバックに渡されたIDを記録する
+        static CHANGED: Mutex<Vec<i32>> = Mutex::new(Vec::new());
+        extern "C" fn record(id: i32) {
+            CHANGED.lock().unwrap().push(id);
+        }
+
         let mut app = App::default();
+        set_on_change(&mut app, Some(record));
 
-        // Todoを追加
-        let (cstring1, note_ref1) = c_str("タスク1");
-        let result = add_todo(&mut app, 1, note_ref1);
-        assert!(result);
-        assert_eq!(app.todos.len(), 1);
+        let (_note, note) = c_str("タスク");
+        assert!(add_todo(&mut app, 1, note));
+        assert!(add_todo(&mut app, 2, note));
+        assert_eq!(add_todo_auto(&mut app, note), 3);
+        // 追加・削除に失敗した場合は呼び出されない
+        assert!(!add_todo(&mut app, 1, note));
+        assert!(!remove_todo(&mut app, 99));
+        assert!(remove_todo(&mut app, 2));
+        assert_eq!(*CHANGED.lock().unwrap(), vec![1, 2, 3, 2]);
 
-        // 2つ目のTodoを追加
-        let (cstring2, note_ref2) = c_str("タスク2");
-        add_todo(&mut app, 2, note_ref2);
+        // 解除した後は呼び出されない
+        set_on_change(&mut app, None);
+        assert!(add_todo(&mut app, 4, note));
+        assert!(remove_todo(&mut app, 1));
+        assert_eq!(CHANGED.lock().unwrap().len(), 4);
+    }
 
-        assert_eq!(app.todos.len(), 2);
-        assert_eq!(app.todos[0].id, 1);
-        assert_eq!(app.todos[0].note.to_str(), "タスク1");
-        assert_eq!(app.todos[1].id, 2);
-        assert_eq!(app.todos[1].note.to_str(), "タスク2");
+    #[test]
+    fn test_on_change_bulk_paths() {
+        use std::sync::Mutex;
 
-        // CStringを変数に保持して、関数を抜けるまで生存期間を保証
-        let _ = (cstring1, cstring2);
+        // コールバックに渡されたIDを記録する
+        static CHANGED: Mutex<Vec<i32>> = Mutex::new(Vec::new());
+        extern "C" fn record(id: i32) {
+            CHANGED.lock().unwrap().push(id);
+        }
+        fn take_changed() -> Vec<i32> {
+            std::mem::take(&mut *CHANGED.lock().unwrap())
+        }
+
+        let mut app = app_with(&[(10, "a"), (30, "済:c")]);
+        set_on_change(&mut app, Some(record));
+        let snapshot = app_snapshot(&app);
+        let (_note, note) = c_str("b");
+
+        assert_eq!(insert_sorted_by_id(&mut app, 20, note), 1);
+        assert_eq!(take_changed(), vec![20]);
+        // 挿入できなかった場合は呼び出されない
+        assert_eq!(insert_sorted_by_id(&mut app, 20, note), -1);
+        assert!(take_changed().is_empty());
+
+        let notes = [note, note, note];
+        assert_eq!(
+            add_todos(&mut app, [40, 10, 50][..].into(), notes[..].into()),
+            2
+        );
+        assert_eq!(take_changed(), vec![40, 50]);
+
+        let src = app_with(&[(60, "済:f"), (70, "g")]);
+        assert_eq!(append_and_consume(&mut app, Box::new(src).into()), 2);
+        assert_eq!(take_changed(), vec![60, 70]);
+
+        let drained = drain_matching(&mut app, is_finished, std::ptr::null_mut());
+        assert_eq!(take_changed(), vec![30, 60]);
+        app_free(drained);
+
+        // 復元では差分のIDだけが、削除されたもの、追加されたものの順に呼び出される
+        assert!(app_restore(&mut app, snapshot[..].into()));
+        assert_eq!(take_changed(), vec![20, 40, 50, 70, 30]);
+
+        clear_todos(&mut app);
+        assert_eq!(take_changed(), vec![10, 30]);
     }
 
     #[test]
-    fn test_get_todo_count() {
+    fn test_add_todo_kv() {
         let mut app = App::default();
-        assert_eq!(get_todo_count(&app), 0);
 
-        // Todoを追加
-        let (cstring, note_ref) = c_str("テスト");
-        add_todo(&mut app, 1, note_ref);
+        let (_key, key) = c_str("優先度");
+        let (_value, value) = c_str("高");
+        assert!(add_todo_kv(&mut app, 1, key, value));
+        let (_empty, empty) = c_str("");
+        assert!(add_todo_kv(&mut app, 2, empty, empty));
 
-        assert_eq!(get_todo_count(&app), 1);
+        assert_eq!(app.note_at(0), Some("優先度: 高".to_string()));
+        assert_eq!(app.note_at(1), Some(": ".to_string()));
 
-        // CStringを変数に保持
-        let _ = cstring;
+        // 同じIDは追加されない
+        assert!(!add_todo_kv(&mut app, 1, key, value));
+        assert_eq!(get_todo_count(&app), 2);
     }
 
     #[test]
-    fn test_get_todo_id_at() {
+    fn test_add_todo_kv_stops_at_nul() {
+        // char_p::Refは最初のNULで終わるため、キーと値のどちらに途中のNULがあっても
+        // それ以降はノートに含まれず、NULを含むノートが保存されることはない
         let mut app = App::default();
+        let key_bytes = b"ke\0y\0";
+        let value_bytes = b"va\0lue\0";
+        let key = char_p::Ref::from(CStr::from_bytes_until_nul(key_bytes).unwrap());
+        let value = char_p::Ref::from(CStr::from_bytes_until_nul(value_bytes).unwrap());
+        let (_clean, clean) = c_str("clean");
 
-        // 範囲外のインデックスにアクセス
-        assert_eq!(get_todo_id_at(&app, 0), -1);
+        assert!(add_todo_kv(&mut app, 1, key, clean));
+        assert!(add_todo_kv(&mut app, 2, clean, value));
+        assert_eq!(app.note_at(0), Some("ke: clean".to_string()));
+        assert_eq!(app.note_at(1), Some("clean: va".to_string()));
+        assert!(app.todos.iter().all(|t| !t.note.to_bytes().contains(&0)));
+    }
 
-        // Todoを追加
-        let (cstring, note_ref) = c_str("テスト");
-        add_todo(&mut app, 42, note_ref);
+    #[test]
+    fn test_has_todo() {
+        let mut app = App::default();
+        assert!(!has_todo(&app, 0));
+        assert!(!has_todo(&app, 1));
 
-        assert_eq!(get_todo_id_at(&app, 0), 42);
-        // 範囲外のインデックスにアクセス
-        assert_eq!(get_todo_id_at(&app, 1), -1);
+        let (_note, note) = c_str("タスク");
+        assert!(add_todo(&mut app, 1, note));
+        assert!(add_todo(&mut app, -3, note));
+        assert!(has_todo(&app, 1));
+        assert!(has_todo(&app, -3));
+        assert!(!has_todo(&app, 2));
 
-        // CStringを変数に保持
-        let _ = cstring;
+        assert!(remove_todo(&mut app, 1));
+        assert!(!has_todo(&app, 1));
+        assert!(has_todo(&app, -3));
     }
 
     #[test]
-    fn test_get_todo_note_at() {
+    fn test_app_snapshot_and_restore() {
+        let mut app = app_with_all_fields();
+        let snapshot_todos = app.todos.to_vec();
+        let snapshot = app_snapshot(&app);
+
+        // スナップショット後の変更を元に戻す
+        let (_note, note) = c_str("追加");
+        assert!(add_todo(&mut app, 3, note));
+        assert!(remove_todo(&mut app, 1));
+        swap_notes(&mut app, 0, 1);
+        set_todo_done(&mut app, -5, false);
+        set_todo_priority(&mut app, 300, Priority::High);
+        set_category_at(&mut app, 1, note);
+
+        assert!(app_restore(&mut app, snapshot[..].into()));
+        assert_eq!(app.todos.len(), snapshot_todos.len());
+        for (restored, expected) in app.todos.iter().zip(snapshot_todos.iter()) {
+            assert_eq!(restored.id, expected.id);
+            assert_eq!(restored.note, expected.note);
+            assert_eq!(restored.status, expected.status);
+            assert_eq!(restored.done, expected.done);
+            assert_eq!(restored.category, expected.category);
+            assert_eq!(restored.priority, expected.priority);
+        }
+        assert_eq!(app.todos[..], snapshot_todos[..]);
+
+        // 同じスナップショットから何度でも復元できる
+        clear_todos(&mut app);
+        assert!(app_restore(&mut app, snapshot[..].into()));
+        assert_eq!(app.todos[..], snapshot_todos[..]);
+        assert_eq!(&app_snapshot(&app)[..], &snapshot[..]);
+        free_u8_vec(snapshot);
+    }
+
+    #[test]
+    fn test_app_restore_rejects_malformed_blob() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        let snapshot = app_snapshot(&app_with(&[(9, "x")]));
+
+        // 途中で終わっているもの、バージョンが異なるもの、空のもの
+        let truncated = &snapshot[..snapshot.len() - 1];
+        let mut other_version = snapshot.to_vec();
+        other_version[0] ^= 0xff;
+        for blob in [truncated, &other_version[..], &[]] {
+            assert!(!app_restore(&mut app, blob.into()));
+            assert_eq!(ids_of(&app), vec![1, 2]);
+        }
+    }
+
+    #[test]
+    fn test_app_restore_respects_max_todos() {
+        let snapshot = app_snapshot(&app_with(&[(1, "a"), (2, "b"), (3, "c")]));
+        let mut app = app_with(&[(9, "x")]);
+
+        // 上限を超えるスナップショットは復元しない
+        set_max_todos(&mut app, 2);
+        assert!(!app_restore(&mut app, snapshot[..].into()));
+        assert_eq!(ids_of(&app), vec![9]);
+
+        set_max_todos(&mut app, 3);
+        assert!(app_restore(&mut app, snapshot[..].into()));
+        assert_eq!(ids_of(&app), vec![1, 2, 3]);
+        assert_eq!(app.max_todos, 3);
+    }
+
+    #[test]
+    fn test_todo_priority() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        assert_eq!(app.todos[0].priority, Priority::Medium);
+
+        for priority in [Priority::Low, Priority::Medium, Priority::High] {
+            assert!(set_todo_priority(&mut app, 2, priority));
+            assert_eq!(get_todo_priority(&app, 2), priority);
+            // 他のTodoは変更されない
+            assert_eq!(get_todo_priority(&app, 1), Priority::Medium);
+        }
+    }
+
+    #[test]
+    fn test_todo_priority_missing_id() {
+        let mut app = app_with(&[(1, "a")]);
+        assert!(!set_todo_priority(&mut app, 2, Priority::High));
+        assert_eq!(get_todo_priority(&app, 2), Priority::Medium);
+        assert_eq!(get_todo_priority(&App::default(), 1), Priority::Medium);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_priority_json_roundtrip() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        set_todo_priority(&mut app, 2, Priority::Low);
+
+        // 既定値の優先度は出力されない
+        let json = app_to_json(&app);
+        assert_eq!(
+            json.to_str(),
+            r#"[{"id":1,"note":"a","status":"todo","done":false},{"id":2,"note":"b","status":"todo","done":false,"priority":"low"}]"#
+        );
+        assert!(json_roundtrip_ok(&app));
+
+        let restored = app_from_json(json.as_ref());
+        assert_eq!(get_todo_priority(&restored, 1), Priority::Medium);
+        assert_eq!(get_todo_priority(&restored, 2), Priority::Low);
+    }
+
+    #[test]
+    fn test_count_notes_containing_char() {
+        let app = app_with(&[
+            (1, "buy milk"),
+            (2, "牛乳を買う"),
+            (3, "mail a letter"),
+            (4, "卵を買う、卵を焼く"),
+            (5, ""),
+        ]);
+
+        // ASCII文字
+        assert_eq!(count_notes_containing_char(&app, 'l' as u32), 2);
+        // マルチバイト文字（複数回含まれても1件）
+        assert_eq!(count_notes_containing_char(&app, '卵' as u32), 1);
+        assert_eq!(count_notes_containing_char(&app, 'を' as u32), 2);
+        // どのノートにも含まれない文字
+        assert_eq!(count_notes_containing_char(&app, 'z' as u32), 0);
+        assert_eq!(count_notes_containing_char(&app, '🍣' as u32), 0);
+    }
+
+    #[test]
+    fn test_count_notes_containing_char_invalid_scalar() {
+        let app = app_with(&[(1, "a"), (2, "\u{fffd}")]);
+        // サロゲートと範囲外の値はどれにも一致しない
+        assert_eq!(count_notes_containing_char(&app, 0xd800), 0);
+        assert_eq!(count_notes_containing_char(&app, 0x11_0000), 0);
+        assert_eq!(count_notes_containing_char(&app, u32::MAX), 0);
+        assert_eq!(count_notes_containing_char(&App::default(), 'a' as u32), 0);
+    }
+
+    #[test]
+    fn test_count_done_and_pending() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")]);
+        assert_eq!((count_done(&app), count_pending(&app)), (0, 5));
+
+        assert!(set_todo_done(&mut app, 1, true));
+        assert!(set_todo_done(&mut app, 4, true));
+        // 状態を完了にした場合も完了として数える
+        assert!(set_status_at(&mut app, 2, Status::Done));
+        assert_eq!(count_done(&app), 3);
+        assert_eq!(count_pending(&app), 2);
+        assert_eq!(count_done(&app) + count_pending(&app), get_todo_count(&app));
+
+        assert!(set_todo_done(&mut app, 1, false));
+        assert_eq!((count_done(&app), count_pending(&app)), (2, 3));
+        assert_eq!(count_done(&app) + count_pending(&app), get_todo_count(&app));
+
+        let empty = App::default();
+        assert_eq!((count_done(&empty), count_pending(&empty)), (0, 0));
+    }
+
+    #[test]
+    fn test_set_max_todos() {
         let mut app = App::default();
+        assert_eq!(app.max_todos, 0);
+        set_max_todos(&mut app, 2);
 
-        // 範囲外のインデックスにアクセス
-        let empty_note = get_todo_note_at(&app, 0);
-        assert_eq!(empty_note.to_str(), "");
+        let (_note, note) = c_str("タスク");
+        assert!(add_todo(&mut app, 1, note));
+        assert!(add_todo(&mut app, 2, note));
+        // 上限に達したら追加しない
+        assert!(!add_todo(&mut app, 3, note));
+        assert_eq!(add_todo_checked(&mut app, 3, note), AddResult::LimitReached);
+        // IDの重複は上限より先に報告する
+        assert_eq!(add_todo_checked(&mut app, 1, note), AddResult::DuplicateId);
+        assert_eq!(add_todo_auto(&mut app, note), -1);
+        assert_eq!(insert_sorted_by_id(&mut app, 0, note), -1);
+        assert_eq!(get_todo_count(&app), 2);
 
-        // Todoを追加
-        let (cstring, note_ref) = c_str("重要なタスク");
-        add_todo(&mut app, 1, note_ref);
+        // 上限を引き上げると再び追加できる
+        set_max_todos(&mut app, 3);
+        assert!(add_todo(&mut app, 3, note));
+        assert!(!add_todo(&mut app, 4, note));
 
-        let retrieved_note = get_todo_note_at(&app, 0);
-        assert_eq!(retrieved_note.to_str(), "重要なタスク");
+        // 削除して空きができた場合も追加できる
+        assert!(remove_todo(&mut app, 1));
+        assert!(add_todo(&mut app, 4, note));
 
-        // 範囲外のインデックスにアクセス
-        let empty_note2 = get_todo_note_at(&app, 1);
-        assert_eq!(empty_note2.to_str(), "");
+        // 0は無制限
+        set_max_todos(&mut app, 0);
+        for id in 5..20 {
+            assert!(add_todo(&mut app, id, note));
+        }
+        assert_eq!(get_todo_count(&app), 18);
 
-        // CStringを変数に保持
-        let _ = cstring;
+        // 上限を現在の数より小さくしても既存のTodoは残る
+        set_max_todos(&mut app, 1);
+        assert_eq!(get_todo_count(&app), 18);
+        assert!(!add_todo(&mut app, 100, note));
+    }
+
+    #[test]
+    fn test_add_todos_respects_max_todos() {
+        let mut app = app_with(&[(1, "a")]);
+        set_max_todos(&mut app, 3);
+
+        let (_n, n) = c_str("n");
+        let notes = [n, n, n, n];
+        // 重複するIDは数に含めず、上限に達した時点で打ち切る
+        assert_eq!(
+            add_todos(&mut app, [1, 2, 3, 4][..].into(), notes[..].into()),
+            2
+        );
+        assert_eq!(ids_of(&app), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_append_and_consume_respects_max_todos() {
+        let mut app = app_with(&[(1, "a")]);
+        set_max_todos(&mut app, 3);
+
+        // 上限に達した時点で打ち切り、残りはsrcとともに解放される
+        let src = app_with(&[(2, "b"), (3, "c"), (4, "d")]);
+        assert_eq!(append_and_consume(&mut app, Box::new(src).into()), 2);
+        assert_eq!(ids_of(&app), vec![1, 2, 3]);
+
+        // 上限に達している場合は何も追加しない
+        let src = app_with(&[(5, "e")]);
+        assert_eq!(append_and_consume(&mut app, Box::new(src).into()), 0);
+        assert_eq!(ids_of(&app), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_toggle_todo_done() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+
+        assert!(toggle_todo_done(&mut app, 1));
+        assert!(is_todo_done(&app, 1));
+        assert_eq!(app.todos[0].status, Status::Done);
+        // 他のTodoは変更されない
+        assert!(!is_todo_done(&app, 2));
+
+        // 2回反転すると元に戻る
+        assert!(!toggle_todo_done(&mut app, 1));
+        assert!(!is_todo_done(&app, 1));
+        assert_eq!(app.todos[0].status, Status::Todo);
+    }
+
+    #[test]
+    fn test_toggle_todo_done_missing_id() {
+        let mut app = app_with(&[(1, "a")]);
+        set_todo_done(&mut app, 1, true);
+
+        assert!(!toggle_todo_done(&mut app, 2));
+        assert!(is_todo_done(&app, 1));
+        assert!(!toggle_todo_done(&mut App::default(), 1));
+    }
+
+    #[test]
+    fn test_nth_completed_index() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")]);
+        for id in [2, 3, 5] {
+            assert!(set_todo_done(&mut app, id, true));
+        }
+
+        assert_eq!(nth_completed_index(&app, 0), 1);
+        assert_eq!(nth_completed_index(&app, 1), 2);
+        assert_eq!(nth_completed_index(&app, 2), 4);
+        // 完了しているTodoの数以上
+        assert_eq!(nth_completed_index(&app, 3), -1);
+        assert_eq!(nth_completed_index(&app, usize::MAX), -1);
+
+        assert_eq!(nth_completed_index(&app_with(&[(1, "a")]), 0), -1);
+        assert_eq!(nth_completed_index(&App::default(), 0), -1);
+    }
+
+    #[test]
+    fn test_first_pending_index() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c")]);
+        assert_eq!(first_pending_index(&app), 0);
+
+        // 先頭が完了している場合は次の未完了のTodo
+        assert!(set_todo_done(&mut app, 1, true));
+        assert_eq!(first_pending_index(&app), 1);
+    }
+
+    #[test]
+    fn test_first_pending_index_all_done() {
+        let mut app = app_with(&[(1, "a"), (2, "b")]);
+        assert!(set_todo_done(&mut app, 1, true));
+        assert!(set_todo_done(&mut app, 2, true));
+        assert_eq!(first_pending_index(&app), -1);
+    }
+
+    #[test]
+    fn test_first_pending_index_empty() {
+        assert_eq!(first_pending_index(&App::default()), -1);
+    }
+    #[test]
+    fn test_priority_completion_rate() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")]);
+        for id in [1, 2, 3, 4] {
+            set_todo_priority(&mut app, id, Priority::High);
+        }
+        set_todo_done(&mut app, 1, true);
+        set_todo_done(&mut app, 3, true);
+        set_todo_done(&mut app, 4, true);
+
+        // 他の優先度のTodoは数えない
+        assert_eq!(priority_completion_rate(&app, Priority::High), 0.75);
+        assert_eq!(priority_completion_rate(&app, Priority::Medium), 0.0);
+
+        set_todo_done(&mut app, 5, true);
+        assert_eq!(priority_completion_rate(&app, Priority::Medium), 1.0);
+    }
+
+    #[test]
+    fn test_priority_completion_rate_empty_level() {
+        let mut app = app_with(&[(1, "a")]);
+        set_todo_done(&mut app, 1, true);
+        assert_eq!(priority_completion_rate(&app, Priority::Low), 0.0);
+        assert_eq!(
+            priority_completion_rate(&App::default(), Priority::Medium),
+            0.0
+        );
+    }
+    #[test]
+    fn test_set_priority_by_ids() {
+        let mut app = app_with(&[(1, "a"), (2, "b"), (3, "c"), (4, "d")]);
+
+        // 存在しないIDは無視する
+        assert_eq!(
+            set_priority_by_ids(&mut app, [4, 2, 99][..].into(), Priority::Low),
+            2
+        );
+        let priorities: Vec<_> = app.todos.iter().map(|t| t.priority).collect();
+        assert_eq!(
+            priorities,
+            vec![
+                Priority::Medium,
+                Priority::Low,
+                Priority::Medium,
+                Priority::Low
+            ]
+        );
+
+        // 入力の重複は1回として数える
+        assert_eq!(
+            set_priority_by_ids(&mut app, [3, 3, 3][..].into(), Priority::High),
+            1
+        );
+        assert_eq!(get_todo_priority(&app, 3), Priority::High);
+
+        assert_eq!(
+            set_priority_by_ids(&mut app, [][..].into(), Priority::High),
+            0
+        );
+    }
+
+    #[test]
+    fn test_set_priority_by_ids_updates_duplicate_todos() {
+        let mut app = app_with(&[(1, "a"), (1, "b"), (2, "c")]);
+        assert_eq!(
+            set_priority_by_ids(&mut app, [1][..].into(), Priority::High),
+            2
+        );
+        assert_eq!(app.todos[1].priority, Priority::High);
+        assert_eq!(app.todos[2].priority, Priority::Medium);
     }
 }
@@ -0,0 +1,45 @@
+//! `leak-check`フィーチャーの計数はプロセス全体で共有されるため、
+//! 他のテストと並行して実行されないよう独立したテストバイナリにしている
+#![cfg(feature = "leak-check")]
+
+use safer_ffi::prelude::*;
+use safer_ffi_example::*;
+use std::ffi::CString;
+
+#[test]
+fn test_free_string() {
+    let mut app = App::default();
+    let note = CString::new("牛乳を買う").unwrap();
+    add_todo(&mut app, 1, char_p::Ref::from(note.as_ref()));
+    let baseline = live_string_count();
+
+    let mut strings = vec![
+        get_todo_note_at(&app, 0),
+        // 範囲外で返される空文字列も数える
+        get_todo_note_at(&app, 1),
+        most_common_first_word(&app),
+        common_note_prefix(&app),
+        get_category_at(&app, 0),
+    ];
+    #[cfg(feature = "serde")]
+    strings.extend([
+        duplicate_id_pairs(&app),
+        ids_to_json(&app),
+        todo_to_json_at(&app, 0),
+        app_to_canonical_json(&app),
+        category_counts(&app),
+        app_to_json(&app),
+        notes_by_index_json(&app),
+    ]);
+    assert_eq!(live_string_count(), baseline + strings.len());
+    assert_eq!(strings[0].to_str(), "牛乳を買う");
+
+    let last = strings.pop().unwrap();
+    free_char_p_box(last);
+    assert_eq!(live_string_count(), baseline + strings.len());
+
+    for s in strings {
+        free_string(s);
+    }
+    assert_eq!(live_string_count(), baseline);
+}